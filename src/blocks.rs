@@ -3,6 +3,8 @@
 pub mod prelude;
 
 use crate::formatting::config::Config as FormatConfig;
+use crate::formatting::locale::Locale;
+use crate::formatting::Placeholders;
 use crate::BoxedFuture;
 use futures::future::FutureExt;
 use serde::de::{self, Deserializer};
@@ -17,8 +19,9 @@ use std::time::Duration;
 use crate::click::ClickHandler;
 use crate::config::SharedConfig;
 use crate::errors::*;
+use crate::icons::Icon;
 use crate::protocol::i3bar_event::I3BarEvent;
-use crate::widget::Widget;
+use crate::widget::{State, Widget};
 use crate::{Request, RequestCmd};
 
 macro_rules! define_blocks {
@@ -51,6 +54,16 @@ macro_rules! define_blocks {
                     )*
                 }
             }
+
+            /// Placeholders this block provides to its format templates
+            pub fn placeholders(self) -> &'static Placeholders {
+                match self {
+                    $(
+                        $(#[cfg($attr)])?
+                        Self::$block => $block::PLACEHOLDERS,
+                    )*
+                }
+            }
         }
 
         impl<'de> Deserialize<'de> for BlockType {
@@ -137,7 +150,7 @@ define_blocks!(
 
 pub type BlockFuture = BoxedFuture<Result<()>>;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum BlockEvent {
     Click(I3BarEvent),
     UpdateRequest,
@@ -151,6 +164,7 @@ pub struct CommonApi {
     pub request_sender: mpsc::Sender<Request>,
 
     pub error_interval: Duration,
+    pub command_timeout: Duration,
 }
 
 impl CommonApi {
@@ -201,6 +215,27 @@ impl CommonApi {
             .error("Failed to send Request")
     }
 
+    /// Temporarily show `text` instead of the widget. The widget is shown again after `duration`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// api.flash("Copied".into(), Duration::from_secs(2), State::Good).await?;
+    /// ```
+    pub async fn flash(&self, text: String, duration: Duration, state: State) -> Result<()> {
+        self.request_sender
+            .send(Request {
+                block_id: self.id,
+                cmd: RequestCmd::Flash {
+                    text,
+                    duration,
+                    state,
+                },
+            })
+            .await
+            .error("Failed to send Request")
+    }
+
     /// Receive the next event, such as click notification or update request.
     ///
     /// This method should be called regularly to avoid sender blocking. Currently, the runtime is
@@ -264,6 +299,12 @@ impl CommonApi {
             .or_error(|| format!("Icon '{}' not found", icon))
     }
 
+    pub fn get_icon_in_family(&self, family: &str, fraction: f64) -> Result<String> {
+        self.shared_config
+            .get_icon_in_family(family, fraction)
+            .or_error(|| format!("Icon family '{}' not found", family))
+    }
+
     /// Repeatedly call provided async function until it succeeds.
     ///
     /// This function will call `f` in a loop. If it succeeds, the result will be returned.
@@ -308,7 +349,15 @@ pub struct CommonConfig {
     #[serde(default)]
     pub theme_overrides: Option<HashMap<String, String>>,
     #[serde(default)]
-    pub icons_overrides: Option<HashMap<String, String>>,
+    pub icons_overrides: Option<HashMap<String, Icon>>,
+    #[serde(default)]
+    pub locale: Option<Locale>,
+    #[serde(default)]
+    pub format_alt: Option<FormatConfig>,
+    #[serde(default)]
+    pub format_expanded: Option<FormatConfig>,
+    #[serde(default)]
+    pub group: Option<String>,
 
     #[serde(default = "CommonConfig::default_error_interval")]
     pub error_interval: u64,
@@ -319,6 +368,8 @@ pub struct CommonConfig {
 
     #[serde(default)]
     pub if_command: Option<String>,
+    #[serde(default = "CommonConfig::default_command_timeout")]
+    pub command_timeout: u64,
 }
 
 impl CommonConfig {
@@ -326,6 +377,10 @@ impl CommonConfig {
         5
     }
 
+    fn default_command_timeout() -> u64 {
+        120
+    }
+
     pub fn new(from: &mut toml::Value) -> Result<Self> {
         const FIELDS: &[&str] = &[
             "block",
@@ -334,10 +389,15 @@ impl CommonConfig {
             "icons_format",
             "theme_overrides",
             "icons_overrides",
+            "locale",
+            "format_alt",
+            "format_expanded",
+            "group",
             "error_interval",
             "error_format",
             "error_fullscreen_format",
             "if_command",
+            "command_timeout",
         ];
         let mut common_table = Table::new();
         if let Some(table) = from.as_table_mut() {
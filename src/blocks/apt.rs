@@ -10,7 +10,6 @@
 //! ----|--------|--------
 //! `interval` | Update interval in seconds. | `600`
 //! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $count.eng(1) "`
-//! `format_singular` | Same as `format`, but for when exactly one update is available. | `" $icon $count.eng(1) "`
 //! `format_up_to_date` | Same as `format`, but for when no updates are available. | `" $icon $count.eng(1) "`
 //! `warning_updates_regex` | Display block as warning if updates matching regex are available. | `None`
 //! `critical_updates_regex` | Display block as critical if updates matching regex are available. | `None`
@@ -28,8 +27,7 @@
 //! [[block]]
 //! block = "apt"
 //! interval = 1800
-//! format = " $icon $count updates available "
-//! format_singular = " $icon One update available "
+//! format = " $icon $count $count.plural(update, updates) available "
 //! format_up_to_date = " $icon system up to date "
 //! critical_updates_regex = "(linux|linux-lts|linux-zen)"
 //! [[block.click]]
@@ -49,15 +47,18 @@ use regex::Regex;
 use tokio::fs::{create_dir_all, File};
 use tokio::process::Command;
 
+use crate::subprocess::run_command_stdout;
+
 use super::prelude::*;
 
+pub const PLACEHOLDERS: &Placeholders = &[("icon", ValueType::Icon), ("count", ValueType::Number)];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct AptConfig {
     #[default(600.into())]
     interval: Seconds,
     format: FormatConfig,
-    format_singular: FormatConfig,
     format_up_to_date: FormatConfig,
     warning_updates_regex: Option<String>,
     critical_updates_regex: Option<String>,
@@ -68,7 +69,6 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut widget = api.new_widget();
 
     let format = config.format.with_default(" $icon $count.eng(1) ")?;
-    let format_singular = config.format_singular.with_default("$count.eng(1)")?;
     let format_up_to_date = config.format_up_to_date.with_default("$count.eng(1)")?;
 
     let warning_updates_regex = config
@@ -112,14 +112,13 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         .error("Failed to write to config file")?;
 
     loop {
-        let updates = get_updates_list(config_file.to_str().unwrap()).await?;
+        let updates = get_updates_list(config_file.to_str().unwrap(), api.command_timeout).await?;
         let count = get_update_count(&updates);
 
-        widget.set_format(match count {
-            0 => format_up_to_date.clone(),
-            1 => format_singular.clone(),
-            _ => format.clone(),
-        });
+        match count {
+            0 => widget.set_state_format(format_up_to_date.clone()),
+            _ => widget.set_format(format.clone()),
+        }
         widget.set_values(map!(
             "count" => Value::number(count),
             "icon" => Value::icon(api.get_icon("update")?)
@@ -162,23 +161,21 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     }
 }
 
-async fn get_updates_list(config_path: &str) -> Result<String> {
-    Command::new("sh")
-        .env("APT_CONFIG", config_path)
-        .args(["-c", "apt update"])
-        .spawn()
-        .error("Failed to ren `apt update` command")?
-        .wait()
-        .await
-        .error("Failed to run `apt update` command")?;
-    let stdout = Command::new("sh")
-        .env("APT_CONFIG", config_path)
-        .args(["-c", "apt list --upgradable"])
-        .output()
-        .await
-        .error("Problem running apt command")?
-        .stdout;
-    String::from_utf8(stdout).error("apt produced non-UTF8 output")
+async fn get_updates_list(config_path: &str, timeout: Duration) -> Result<String> {
+    run_command_stdout(
+        Command::new("sh")
+            .env("APT_CONFIG", config_path)
+            .args(["-c", "apt update"]),
+        timeout,
+    )
+    .await?;
+    run_command_stdout(
+        Command::new("sh")
+            .env("APT_CONFIG", config_path)
+            .args(["-c", "apt list --upgradable"]),
+        timeout,
+    )
+    .await
 }
 
 fn get_update_count(updates: &str) -> usize {
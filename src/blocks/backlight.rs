@@ -3,7 +3,8 @@
 //! This block reads brightness information directly from the filesystem, so it works under both
 //! X11 and Wayland. The block uses `inotify` to listen for changes in the device's brightness
 //! directly, so there is no need to set an update interval. This block uses DBus to set brightness
-//! level using the mouse wheel.
+//! level using the mouse wheel, or by clicking on `$brightness` displayed as a bar, e.g.
+//! `format = " $icon $brightness.bar(10) "`.
 //!
 //! # Root scaling
 //!
@@ -36,21 +37,7 @@
 //! ```
 //!
 //! # Icons Used
-//! - `backlight_empty` (when brightness between 0 and 6%)
-//! - `backlight_1` (when brightness between 7 and 13%)
-//! - `backlight_2` (when brightness between 14 and 20%)
-//! - `backlight_3` (when brightness between 21 and 26%)
-//! - `backlight_4` (when brightness between 27 and 33%)
-//! - `backlight_5` (when brightness between 34 and 40%)
-//! - `backlight_6` (when brightness between 41 and 46%)
-//! - `backlight_7` (when brightness between 47 and 53%)
-//! - `backlight_8` (when brightness between 54 and 60%)
-//! - `backlight_9` (when brightness between 61 and 67%)
-//! - `backlight_10` (when brightness between 68 and 73%)
-//! - `backlight_11` (when brightness between 74 and 80%)
-//! - `backlight_12` (when brightness between 81 and 87%)
-//! - `backlight_13` (when brightness between 88 and 93%)
-//! - `backlight_full` (when brightness above 94%)
+//! - `backlight` (an icon family from the lowest to the highest brightness)
 
 use std::cmp::max;
 use std::ops::Range;
@@ -90,24 +77,8 @@ const FILE_BRIGHTNESS_AMD: &str = "brightness";
 /// Range of valid values for `root_scaling`
 const ROOT_SCALDING_RANGE: Range<f64> = 0.1..10.;
 
-/// Ordered list of icons used to display lighting progress
-const BACKLIGHT_ICONS: &[&str] = &[
-    "backlight_empty",
-    "backlight_1",
-    "backlight_2",
-    "backlight_3",
-    "backlight_4",
-    "backlight_5",
-    "backlight_6",
-    "backlight_7",
-    "backlight_8",
-    "backlight_9",
-    "backlight_10",
-    "backlight_11",
-    "backlight_12",
-    "backlight_13",
-    "backlight_full",
-];
+pub const PLACEHOLDERS: &Placeholders =
+    &[("icon", ValueType::Icon), ("brightness", ValueType::Number)];
 
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
@@ -154,14 +125,14 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
     loop {
         let brightness = device.brightness().await?;
-        let mut icon_index = (usize::from(brightness) * BACKLIGHT_ICONS.len()) / 101;
+        let mut fraction = f64::from(brightness) / 100.;
         if config.invert_icons {
-            icon_index = BACKLIGHT_ICONS.len() - icon_index - 1;
+            fraction = 1. - fraction;
         }
 
         widget.set_values(map! {
-            "icon" => Value::icon(api.get_icon(BACKLIGHT_ICONS[icon_index])?),
-            "brightness" => Value::percents(brightness)
+            "icon" => Value::icon(api.get_icon_in_family("backlight", fraction)?),
+            "brightness" => Value::percents(brightness).with_slider()
         });
         api.set_widget(&widget).await?;
 
@@ -171,6 +142,12 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 event = api.event() => match event {
                     UpdateRequest => (),
                     Click(click) => {
+                        if let Some(brightness) = click.slider_value() {
+                            device.set_brightness(
+                                (brightness.round() as u8).clamp(config.minimum, config.maximum)
+                            ).await?;
+                            continue;
+                        }
                         match click.button {
                             MouseButton::Left => {
                                 if let Some(brightness) = cycle.next() {
@@ -49,23 +49,14 @@
 //! # Icons Used
 //! - `bat_charging`
 //! - `bat_not_available`
-//! - `bat_10`
-//! - `bat_20`
-//! - `bat_30`
-//! - `bat_40`
-//! - `bat_50`
-//! - `bat_60`
-//! - `bat_70`
-//! - `bat_80`
-//! - `bat_90`
-//! - `bat_full`
+//! - `bat` (an icon family from an empty to a full battery)
 
 use regex::Regex;
 use std::convert::Infallible;
 use std::str::FromStr;
 
 use super::prelude::*;
-use crate::util::battery_level_icon;
+use crate::util::battery_level_fraction;
 
 mod apc_ups;
 mod sysfs;
@@ -73,6 +64,13 @@ mod upower;
 
 // make_log_macro!(debug, "battery");
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("percentage", ValueType::Number),
+    ("time", ValueType::Text),
+    ("power", ValueType::Number),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct BatteryConfig {
@@ -135,11 +133,13 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
         match info {
             Some(info) => {
-                widget.set_format(match info.status {
-                    BatteryStatus::Empty => format_empty.clone(),
-                    BatteryStatus::Full | BatteryStatus::NotCharging => format_full.clone(),
-                    _ => format.clone(),
-                });
+                match info.status {
+                    BatteryStatus::Empty => widget.set_state_format(format_empty.clone()),
+                    BatteryStatus::Full | BatteryStatus::NotCharging => {
+                        widget.set_state_format(format_full.clone());
+                    }
+                    _ => widget.set_format(format.clone()),
+                }
 
                 let mut values = map!(
                     "percentage" => Value::percents(info.capacity)
@@ -159,13 +159,14 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 });
 
                 let (icon, state) = match (info.status, info.capacity) {
-                    (BatteryStatus::Empty, _) => (battery_level_icon(0, false), State::Critical),
-                    (BatteryStatus::Full, _) => (battery_level_icon(100, false), State::Idle),
-                    (status, capacity) => (
-                        battery_level_icon(capacity as u8, status == BatteryStatus::Charging),
-                        if status == BatteryStatus::Charging {
-                            State::Good
-                        } else if capacity <= config.critical {
+                    (BatteryStatus::Empty, _) => {
+                        (api.get_icon_in_family("bat", 0.)?, State::Critical)
+                    }
+                    (BatteryStatus::Full, _) => (api.get_icon_in_family("bat", 1.)?, State::Idle),
+                    (BatteryStatus::Charging, _) => (api.get_icon("bat_charging")?, State::Good),
+                    (_, capacity) => (
+                        api.get_icon_in_family("bat", battery_level_fraction(capacity))?,
+                        if capacity <= config.critical {
                             State::Critical
                         } else if capacity <= config.warning {
                             State::Warning
@@ -178,14 +179,14 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                         },
                     ),
                 };
-                values.insert("icon".into(), Value::icon(api.get_icon(icon)?));
+                values.insert("icon".into(), Value::icon(icon));
 
                 widget.set_values(values);
                 widget.state = state;
                 api.set_widget(&widget).await?;
             }
             None => {
-                widget.set_format(missing_format.clone());
+                widget.set_state_format(missing_format.clone());
                 widget.set_values(map!("icon" => Value::icon(api.get_icon("bat_not_available")?)));
                 widget.state = State::Critical;
                 api.set_widget(&widget).await?;
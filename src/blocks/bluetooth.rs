@@ -48,6 +48,13 @@ use zbus::fdo::{ObjectManagerProxy, PropertiesProxy};
 
 make_log_macro!(debug, "bluetooth");
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("name", ValueType::Text),
+    ("percentage", ValueType::Number),
+    ("available", ValueType::Flag),
+];
+
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 struct BluetoothConfig {
@@ -90,8 +97,8 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                     debug!("Showing device as connected");
                 } else {
                     debug!("Showing device as disconnected");
-                    widget.set_format(disconnected_format.clone());
-                    widget.state = State::Idle;
+                    widget.set_state_format(disconnected_format.clone());
+                    widget.state = State::Disconnected;
                 }
                 widget.set_values(values);
 
@@ -100,8 +107,8 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             // Unavailable
             None => {
                 debug!("Showing device as unavailable");
-                widget.state = State::Idle;
-                widget.set_format(disconnected_format.clone());
+                widget.state = State::Disconnected;
+                widget.set_state_format(disconnected_format.clone());
                 widget.set_values(map!("icon" => Value::icon(api.get_icon("bluetooth")?)));
                 api.set_widget(&widget).await?;
             }
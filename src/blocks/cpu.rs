@@ -5,7 +5,6 @@
 //! Key | Values | Default
 //! ----|--------|--------
 //! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $utilization "`
-//! `format_alt` | If set, block will switch between `format` and `format_alt` on every click | `None`
 //! `interval` | Update interval in seconds | `5`
 //!
 //! Placeholder      | Value                                                          | Type   | Unit
@@ -16,8 +15,17 @@
 //! `barchart`       | Utilization of all logical CPUs presented as a barchart        | Text   | -
 //! `frequency`      | Average CPU frequency                                          | Number | Hz
 //! `frequency<N>`   | Frequency of Nth logical CPU                                   | Number | Hz
+//! `cores`          | Per-core values, see below                                     | List   | -
 //! `boost`          | CPU turbo boost status (may be absent if CPU is not supported) | Text   | -
 //!
+//! Each item of `cores` has the following placeholders:
+//!
+//! Placeholder   | Value                            | Type   | Unit
+//! --------------|----------------------------------|--------|-----
+//! `core`        | The index of the logical CPU     | Number | -
+//! `utilization` | Utilization of the logical CPU   | Number | %
+//! `frequency`   | Frequency of the logical CPU     | Number | Hz
+//!
 //! # Example
 //!
 //! ```toml
@@ -28,6 +36,14 @@
 //! format_alt = " $icon $frequency.eng(){ $boost.str()|} "
 //! ```
 //!
+//! Display a bar per logical CPU:
+//!
+//! ```toml
+//! [[block]]
+//! block = "cpu"
+//! format = " $icon $cores{$utilization.bar(1)} "
+//! ```
+//!
 //! # Icons Used
 //! - `cpu`
 //! - `cpu_boost_on`
@@ -44,11 +60,28 @@ use crate::util::read_file;
 const CPU_BOOST_PATH: &str = "/sys/devices/system/cpu/cpufreq/boost";
 const CPU_NO_TURBO_PATH: &str = "/sys/devices/system/cpu/intel_pstate/no_turbo";
 
+/// Placeholders of each item of `cores`
+const CORE_PLACEHOLDERS: &Placeholders = &[
+    ("core", ValueType::Number),
+    ("utilization", ValueType::Number),
+    ("frequency", ValueType::Number),
+];
+
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("utilization", ValueType::Number),
+    ("utilization<N>", ValueType::Number),
+    ("barchart", ValueType::Text),
+    ("frequency", ValueType::Number),
+    ("frequency<N>", ValueType::Number),
+    ("cores", ValueType::List(CORE_PLACEHOLDERS)),
+    ("boost", ValueType::Icon),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct CpuConfig {
     format: FormatConfig,
-    format_alt: Option<FormatConfig>,
     #[default(5.into())]
     interval: Seconds,
 }
@@ -56,15 +89,9 @@ struct CpuConfig {
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = CpuConfig::deserialize(config).config_error()?;
 
-    let mut format = config.format.with_default(" $icon $utilization ")?;
-    let mut format_alt = match config.format_alt {
-        Some(f) => Some(f.with_default("")?),
-        None => None,
-    };
-
     let mut widget = api
         .new_widget()
-        .with_format(format.clone());
+        .with_format(config.format.with_default(" $icon $utilization ")?);
 
     let boost_icon_on = api.get_icon("cpu_boost_on")?;
     let boost_icon_off = api.get_icon("cpu_boost_off")?;
@@ -120,6 +147,18 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 Value::percents(utilization * 100.),
             );
         }
+        let cores = utilizations
+            .iter()
+            .enumerate()
+            .map(|(i, utilization)| {
+                map! {
+                    "core" => Value::number(i + 1),
+                    "utilization" => Value::percents(utilization * 100.),
+                    [if let Some(freq) = freqs.get(i)] "frequency" => Value::hertz(*freq),
+                }
+            })
+            .collect();
+        values.insert("cores".into(), Value::list(cores).with_instance(0));
 
         widget.set_values(values);
         widget.state = match utilization_avg {
@@ -130,19 +169,9 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         };
         api.set_widget(&widget).await?;
 
-        loop {
-            select! {
-                _ = timer.tick() => break,
-                Click(click) = api.event() => {
-                    if click.button == MouseButton::Left {
-                        if let Some(ref mut format_alt) = format_alt {
-                            std::mem::swap(format_alt, &mut format);
-                            widget.set_format(format.clone());
-                            break;
-                        }
-                    }
-                }
-            }
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
         }
     }
 }
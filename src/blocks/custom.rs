@@ -5,7 +5,7 @@
 //! {"icon": "...", "state": "...", "text": "...", "short_text": "..."}
 //! ```
 //! `icon` is optional (default "")
-//! `state` is optional, it may be Idle, Info, Good, Warning, Critical (default Idle)
+//! `state` is optional, it may be Idle, Info, Good, Warning, Critical, Muted, Disconnected (default Idle)
 //! `short_text` is optional.
 //!
 //! # Configuration
@@ -13,7 +13,7 @@
 //! Key | Values | Default
 //! ----|--------|--------
 //! `format` | A string to customise the output of this block. See below for available placeholders. | <code>"{ $icon&vert} $text "</code>
-//! `command` | Shell command to execute & display | `None`
+//! `command` | Shell command to execute & display. If it exits with a non-zero status, the block shows an error with its stderr. `${env:...}`, `${file:...}` and `${cmd:...}` are replaced when the configuration is loaded (see the README); write `$${` to pass `${` to the shell | `None`
 //! `persistent` | Run command in the background; update display for each output line of the command | `false`
 //! `cycle` | Commands to execute and change when the button is clicked | `None`
 //! `interval` | Update interval in seconds (or "once" to update only once) | `10`
@@ -92,11 +92,18 @@
 //! - Use `shellexpand`
 
 use super::prelude::*;
+use crate::subprocess::run_command_stdout;
 use inotify::{Inotify, WatchMask};
 use std::process::Stdio;
 use tokio::io::{self, AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("text", ValueType::Text),
+    ("short_text", ValueType::Text),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct CustomConfig {
@@ -225,14 +232,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
         loop {
             // Run command
-            let output = Command::new(&shell)
-                .args(["-c", &cmd])
-                .output()
-                .await
-                .error("failed to run command")?;
-            let stdout = std::str::from_utf8(&output.stdout)
-                .error("the output of command is invalid UTF-8")?
-                .trim();
+            let output =
+                run_command_stdout(Command::new(&shell).args(["-c", &cmd]), api.command_timeout)
+                    .await?;
+            let stdout = output.trim();
 
             update_bar(
                 stdout,
@@ -1,12 +1,14 @@
 //! A block controled by the DBus
 //!
 //! This block creates a new DBus object in `rs.i3status` service. This object implements
-//! `rs.i3status.custom` interface which allows you to set block's icon, text and state.
+//! `rs.i3status.custom` interface which allows you to set block's icon, text and state, or to
+//! briefly show a message.
 //!
 //! Output of `busctl --user introspect rs.i3status /<path> rs.i3status.custom`:
 //! ```text
 //! NAME                                TYPE      SIGNATURE RESULT/VALUE FLAGS
 //! rs.i3status.custom                  interface -         -            -
+//! .Flash                              method    sts       -            -
 //! .SetIcon                            method    s         s            -
 //! .SetState                           method    s         s            -
 //! .SetText                            method    ss        s            -
@@ -41,6 +43,8 @@
 //! busctl --user call rs.i3status /my_path rs.i3status.custom SetIcon s music
 //! # set state to 'good'
 //! busctl --user call rs.i3status /my_path rs.i3status.custom SetState s good
+//! # show 'done' for 2000 milliseconds
+//! busctl --user call rs.i3status /my_path rs.i3status.custom Flash sts done 2000 good
 //! ```
 //!
 //! Because it's impossible to publish objects to the same name from different
@@ -62,6 +66,12 @@ static DBUS_CONNECTION: async_once_cell::OnceCell<Result<zbus::Connection>> =
 
 const DBUS_NAME: &str = "rs.i3status";
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("text", ValueType::Text),
+    ("short_text", ValueType::Text),
+];
+
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 struct CustomDBusConfig {
@@ -104,17 +114,30 @@ impl Block {
     }
 
     async fn set_state(&mut self, state: &str) -> fdo::Result<()> {
-        self.widget.state = match state {
-            "idle" => State::Idle,
-            "info" => State::Info,
-            "good" => State::Good,
-            "warning" => State::Warning,
-            "critical" => State::Critical,
-            _ => return Err(Error::new(format!("'{state}' is not a valid state")).into()),
-        };
+        self.widget.state = parse_state(state)?;
         self.api.set_widget(&self.widget).await?;
         Ok(())
     }
+
+    async fn flash(&self, text: String, duration_ms: u64, state: &str) -> fdo::Result<()> {
+        self.api
+            .flash(text, Duration::from_millis(duration_ms), parse_state(state)?)
+            .await?;
+        Ok(())
+    }
+}
+
+fn parse_state(state: &str) -> Result<State> {
+    Ok(match state {
+        "idle" => State::Idle,
+        "info" => State::Info,
+        "good" => State::Good,
+        "warning" => State::Warning,
+        "critical" => State::Critical,
+        "muted" => State::Muted,
+        "disconnected" => State::Disconnected,
+        _ => return Err(Error::new(format!("'{state}' is not a valid state"))),
+    })
 }
 
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
@@ -5,6 +5,7 @@
 //! Key | Values | Default
 //! ----|--------|--------
 //! `path` | Path to collect information from. Supports path expansions e.g. `~`. | `"/"`
+//! `paths` | Additional paths to collect information from, available via `$paths`. The state of the block is still determined by `path` only. | `[]`
 //! `interval` | Update time in seconds | `20`
 //! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $available "`
 //! `warning` | A value which will trigger warning block state | `20.0`
@@ -21,6 +22,10 @@
 //! `used`       | Used disk space                                                    | Number | Bytes
 //! `free`       | Free disk space                                                    | Number | Bytes
 //! `available`  | Available disk space (free disk space minus reserved system space) | Number | Bytes
+//! `paths`      | Information about each of `paths`                                  | List   | -
+//!
+//! Each item of `paths` has `path`, `percentage`, `total`, `used`, `free` and `available`
+//! placeholders, which have the same meaning as above.
 //!
 //! # Example
 //!
@@ -34,6 +39,15 @@
 //! format = " $icon $available.eng(2) "
 //! ```
 //!
+//! Display multiple mount points in one block:
+//!
+//! ```toml
+//! [[block]]
+//! block = "disk_space"
+//! paths = ["/", "/home", "/mnt/data"]
+//! format = " $icon $paths{$path $available}.join(\" | \") "
+//! ```
+//!
 //! Update block on right click:
 //!
 //! ```toml
@@ -62,11 +76,33 @@ pub enum InfoType {
     Used,
 }
 
+/// Placeholders of each item of `paths`
+const PATH_PLACEHOLDERS: &Placeholders = &[
+    ("path", ValueType::Text),
+    ("percentage", ValueType::Number),
+    ("total", ValueType::Number),
+    ("used", ValueType::Number),
+    ("free", ValueType::Number),
+    ("available", ValueType::Number),
+];
+
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("path", ValueType::Text),
+    ("percentage", ValueType::Number),
+    ("total", ValueType::Number),
+    ("used", ValueType::Number),
+    ("free", ValueType::Number),
+    ("available", ValueType::Number),
+    ("paths", ValueType::List(PATH_PLACEHOLDERS)),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct DiskSpaceConfig {
     #[default("/".into())]
     path: ShellString,
+    paths: Vec<ShellString>,
     info_type: InfoType,
     format: FormatConfig,
     alert_unit: Option<String>,
@@ -95,32 +131,23 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     };
 
     let path = config.path.expand()?;
+    let paths = config
+        .paths
+        .iter()
+        .map(|p| p.expand().map(|p| p.into_owned()))
+        .collect::<Result<Vec<_>>>()?;
 
     loop {
-        let statvfs = statvfs(&*path).error("failed to retrieve statvfs")?;
-
-        let total = (statvfs.blocks() as u64) * (statvfs.fragment_size() as u64);
-        let used = ((statvfs.blocks() as u64) - (statvfs.blocks_free() as u64))
-            * (statvfs.fragment_size() as u64);
-        let available = (statvfs.blocks_available() as u64) * (statvfs.block_size() as u64);
-        let free = (statvfs.blocks_free() as u64) * (statvfs.block_size() as u64);
-
-        let result = match config.info_type {
-            InfoType::Available => available,
-            InfoType::Free => free,
-            InfoType::Used => used,
-        } as f64;
-
-        let percentage = result / (total as f64) * 100.;
-        widget.set_values(map! {
-            "icon" => Value::icon(api.get_icon("disk_drive")?),
-            "path" => Value::text(path.to_string()),
-            "percentage" => Value::percents(percentage),
-            "total" => Value::bytes(total as f64),
-            "used" => Value::bytes(used as f64),
-            "available" => Value::bytes(available as f64),
-            "free" => Value::bytes(free as f64),
-        });
+        let (mut values, result, percentage) = disk_values(&path, config.info_type)?;
+
+        let mut paths_values = Vec::with_capacity(paths.len());
+        for path in &paths {
+            paths_values.push(disk_values(path, config.info_type)?.0);
+        }
+
+        values.insert("icon".into(), Value::icon(api.get_icon("disk_drive")?));
+        values.insert("paths".into(), Value::list(paths_values).with_instance(0));
+        widget.set_values(values);
 
         // Send percentage to alert check if we don't want absolute alerts
         let alert_val_in_config_units = match unit {
@@ -164,3 +191,32 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         }
     }
 }
+
+/// Returns the values for a given path, along with the value of `info_type` and its percentage
+fn disk_values(path: &str, info_type: InfoType) -> Result<(Values, f64, f64)> {
+    let statvfs = statvfs(path).or_error(|| format!("failed to retrieve statvfs for '{path}'"))?;
+
+    let total = (statvfs.blocks() as u64) * (statvfs.fragment_size() as u64);
+    let used = ((statvfs.blocks() as u64) - (statvfs.blocks_free() as u64))
+        * (statvfs.fragment_size() as u64);
+    let available = (statvfs.blocks_available() as u64) * (statvfs.block_size() as u64);
+    let free = (statvfs.blocks_free() as u64) * (statvfs.block_size() as u64);
+
+    let result = match info_type {
+        InfoType::Available => available,
+        InfoType::Free => free,
+        InfoType::Used => used,
+    } as f64;
+
+    let percentage = result / (total as f64) * 100.;
+    let values = map! {
+        "path" => Value::text(path.to_string()),
+        "percentage" => Value::percents(percentage),
+        "total" => Value::bytes(total as f64),
+        "used" => Value::bytes(used as f64),
+        "available" => Value::bytes(available as f64),
+        "free" => Value::bytes(free as f64),
+    };
+
+    Ok((values, result, percentage))
+}
@@ -7,7 +7,6 @@
 //! ----|--------|--------
 //! `interval` | Update interval in seconds. | `600`
 //! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $count.eng(1) "`
-//! `format_singular` | Same as `format`, but for when exactly one update is available. | `" $icon $count.eng(1) "`
 //! `format_up_to_date` | Same as `format`, but for when no updates are available. | `" $icon $count.eng(1) "`
 //! `warning_updates_regex` | Display block as warning if updates matching regex are available. | `None`
 //! `critical_updates_regex` | Display block as critical if updates matching regex are available. | `None`
@@ -25,8 +24,7 @@
 //! [[block]]
 //! block = "dnf"
 //! interval = 1800
-//! format = " $icon $count.eng(1) updates available "
-//! format_singular = " $icon One update available "
+//! format = " $icon $count.eng(1) $count.plural(update, updates) available "
 //! format_up_to_date = " $icon system up to date "
 //! critical_updates_regex = "(linux|linux-lts|linux-zen)"
 //! [[block.click]]
@@ -43,13 +41,19 @@ use super::prelude::*;
 use regex::Regex;
 use tokio::process::Command;
 
+use crate::subprocess::run_command;
+
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("count", ValueType::Number),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct DnfConfig {
     #[default(600.into())]
     interval: Seconds,
     format: FormatConfig,
-    format_singular: FormatConfig,
     format_up_to_date: FormatConfig,
     warning_updates_regex: Option<String>,
     critical_updates_regex: Option<String>,
@@ -60,7 +64,6 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut widget = api.new_widget();
 
     let format = config.format.with_default(" $icon $count.eng(1) ")?;
-    let format_singular = config.format_singular.with_default(" $icon $count.eng(1) ")?;
     let format_up_to_date = config.format_up_to_date.with_default(" $icon $count.eng(1) ")?;
 
     let warning_updates_regex = config
@@ -77,14 +80,13 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         .error("invalid critical updates regex")?;
 
     loop {
-        let updates = get_updates_list().await?;
+        let updates = get_updates_list(api.command_timeout).await?;
         let count = get_update_count(&updates);
 
-        widget.set_format(match count {
-            0 => format_up_to_date.clone(),
-            1 => format_singular.clone(),
-            _ => format.clone(),
-        });
+        match count {
+            0 => widget.set_state_format(format_up_to_date.clone()),
+            _ => widget.set_format(format.clone()),
+        }
         widget.set_values(map!(
             "icon" => Value::icon(api.get_icon("update")?),
             "count" => Value::number(count)
@@ -127,14 +129,16 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     }
 }
 
-async fn get_updates_list() -> Result<String> {
-    let stdout = Command::new("sh")
-        .env("LC_LANG", "C")
-        .args(["-c", "dnf check-update -q --skip-broken"])
-        .output()
-        .await
-        .error("Failed to run dnf check-update")?
-        .stdout;
+async fn get_updates_list(timeout: Duration) -> Result<String> {
+    // `dnf check-update` exits with 100 if there are updates available
+    let stdout = run_command(
+        Command::new("sh")
+            .env("LC_LANG", "C")
+            .args(["-c", "dnf check-update -q --skip-broken"]),
+        timeout,
+    )
+    .await?
+    .stdout;
     String::from_utf8(stdout).error("dnf produced non-UTF8 output")
 }
 
@@ -34,6 +34,15 @@ use super::prelude::*;
 use std::path::Path;
 use tokio::net::UnixStream;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("total", ValueType::Number),
+    ("running", ValueType::Number),
+    ("stopped", ValueType::Number),
+    ("paused", ValueType::Number),
+    ("images", ValueType::Number),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct DockerConfig {
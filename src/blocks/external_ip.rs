@@ -64,6 +64,36 @@ use crate::util::{country_flag_from_iso_code, new_system_dbus_connection};
 
 const API_ENDPOINT: &str = "https://ipapi.co/json/";
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("ip", ValueType::Text),
+    ("version", ValueType::Text),
+    ("city", ValueType::Text),
+    ("region", ValueType::Text),
+    ("region_code", ValueType::Text),
+    ("country", ValueType::Text),
+    ("country_name", ValueType::Text),
+    ("country_flag", ValueType::Text),
+    ("country_code", ValueType::Text),
+    ("country_code_iso3", ValueType::Text),
+    ("country_capital", ValueType::Text),
+    ("country_tld", ValueType::Text),
+    ("continent_code", ValueType::Text),
+    ("latitude", ValueType::Number),
+    ("longitude", ValueType::Number),
+    ("timezone", ValueType::Text),
+    ("utc_offset", ValueType::Text),
+    ("country_calling_code", ValueType::Text),
+    ("currency", ValueType::Text),
+    ("currency_name", ValueType::Text),
+    ("languages", ValueType::Text),
+    ("country_area", ValueType::Number),
+    ("country_population", ValueType::Number),
+    ("asn", ValueType::Text),
+    ("org", ValueType::Text),
+    ("postal", ValueType::Text),
+    ("in_eu", ValueType::Flag),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct ExternalIpConfig {
@@ -13,8 +13,10 @@
 //! Placeholder     | Value                                                                 | Type | Unit
 //! ----------------|-----------------------------------------------------------------------|------|-----
 //! `title`         | Window's title (may be absent)                                        | Text | -
-//! `marks`         | Window's marks (present only with sway/i3)                            | Text | -
-//! `visible_marks` | Window's marks that do not start with `_` (present only with sway/i3) | Text | -
+//! `marks`         | Window's marks (present only with sway/i3)                            | List | -
+//! `visible_marks` | Window's marks that do not start with `_` (present only with sway/i3) | List | -
+//!
+//! Each item of `marks` and `visible_marks` has a single `mark` placeholder of type Text.
 //!
 //! # Example
 //!
@@ -32,6 +34,15 @@
 //! [[block]]
 //! block = "focused_window"
 //! format = " $title.str(0,21) | Missing "
+//! ```
+//!
+//! Display visible marks in brackets before the title
+//!
+//! ```toml
+//! [[block]]
+//! block = "focused_window"
+//! format = " $visible_marks{[$mark]} $title.str(0,21) |"
+//! ```
 
 use super::prelude::*;
 use swayipc_async::{Connection, Event, EventStream, EventType, WindowChange, WorkspaceChange};
@@ -42,6 +53,15 @@ use tokio::{
     process::{ChildStdout, Command},
 };
 
+/// Placeholders of each item of `marks` and `visible_marks`
+const MARK_PLACEHOLDERS: &Placeholders = &[("mark", ValueType::Text)];
+
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("title", ValueType::Text),
+    ("marks", ValueType::List(MARK_PLACEHOLDERS)),
+    ("visible_marks", ValueType::List(MARK_PLACEHOLDERS)),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct FocusedWindowConfig {
@@ -83,8 +103,8 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 } else {
                     widget.set_values(map! {
                         "title" => Value::text(title.clone()),
-                        "marks" => Value::text(marks.iter().map(|m| format!("[{m}]")).collect()),
-                        "visible_marks" => Value::text(marks.iter().filter(|m| !m.starts_with('_')).map(|m| format!("[{m}]")).collect()),
+                        "marks" => marks_list(marks.iter()),
+                        "visible_marks" => marks_list(marks.iter().filter(|m| !m.starts_with('_'))),
                     });
                 }
                 api.set_widget(&widget).await?;
@@ -93,6 +113,15 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     }
 }
 
+fn marks_list<'a>(marks: impl Iterator<Item = &'a String>) -> Value {
+    Value::list(
+        marks
+            .map(|m| map!("mark" => Value::text(m.clone())))
+            .collect(),
+    )
+    .with_instance(0)
+}
+
 #[async_trait]
 trait Backend {
     async fn get_info(&mut self) -> Result<Info>;
@@ -8,7 +8,7 @@
 //! ----|--------|--------
 //! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $total.eng(1) "`
 //! `interval` | Update interval in seconds | `30`
-//! `token` | A GitHub personal access token with the "notifications" scope | `None`
+//! `token` | A GitHub personal access token with the "notifications" scope. Can be read from a file, a command or an environment variable, e.g. `{ command = "pass show github" }` (see the README). | `None`
 //! `hide_if_total_is_zero` | Hide this block if the total count of notifications is zero | `false`
 //! `critical` | List of notification types that change the block to the critical colour | `None`
 //! `warning` | List of notification types that change the block to the warning colour | `None`
@@ -55,18 +55,47 @@
 //! hide_if_total_is_zero = true
 //! ```
 //!
+//! Show how many notifications there are for each reason when the block is clicked:
+//!
+//! ```toml
+//! [[block]]
+//! block = "github"
+//! token = "..."
+//! format_expanded = " $icon $total.eng(1): $mention.eng(1) mentions, $review_requested.eng(1) reviews, $assign.eng(1) assigned, $comment.eng(1) comments, $ci_activity.eng(1) CI runs "
+//! [[block.click]]
+//! button = "left"
+//! expand = true
+//! ```
+//!
 //! # Icons Used
 //! - `github`
 
 use super::prelude::*;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("total", ValueType::Number),
+    ("assign", ValueType::Number),
+    ("author", ValueType::Number),
+    ("comment", ValueType::Number),
+    ("ci_activity", ValueType::Number),
+    ("invitation", ValueType::Number),
+    ("manual", ValueType::Number),
+    ("mention", ValueType::Number),
+    ("review_requested", ValueType::Number),
+    ("security_alert", ValueType::Number),
+    ("state_change", ValueType::Number),
+    ("subscribed", ValueType::Number),
+    ("team_mention", ValueType::Number),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct GithubConfig {
     #[default(60.into())]
     interval: Seconds,
     format: FormatConfig,
-    token: Option<String>,
+    token: Option<Secret>,
     hide_if_total_is_zero: bool,
     good: Option<Vec<String>>,
     info: Option<Vec<String>>,
@@ -83,11 +112,12 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut interval = config.interval.timer();
     let token = config
         .token
-        .or_else(|| std::env::var("I3RS_GITHUB_TOKEN").ok())
-        .error("Github token not found")?;
+        .unwrap_or_else(|| Secret::env("I3RS_GITHUB_TOKEN"));
 
     loop {
-        let stats = api.recoverable(|| get_stats(&token)).await?;
+        let stats = api
+            .recoverable(|| async { get_stats(token.get().await?).await })
+            .await?;
         if stats.get("total").map_or(false, |x| *x > 0) || !config.hide_if_total_is_zero {
             let mut state = State::Idle;
             'outer: for (list_opt, ret) in [
@@ -3,6 +3,8 @@
 //! This block displays the current color temperature in Kelvin. When scrolling upon the block the color temperature is changed.
 //! A left click on the block sets the color temperature to `click_temp` that is by default to `6500K`.
 //! A right click completely resets the color temperature to its default value (`6500K`).
+//! If the temperature is displayed as a bar from `min_temp` to `max_temp`, e.g.
+//! `$temperature.bar(10, 10000, 1000)`, clicking on the bar sets the temperature.
 //!
 //! # Configuration
 //!
@@ -53,6 +55,10 @@ use crate::subprocess::{spawn_process, spawn_shell};
 use crate::util::has_command;
 use futures::future::pending;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("temperature", ValueType::Number),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct HueshiftConfig {
@@ -115,7 +121,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut current_temp = config.current_temp;
 
     loop {
-        widget.set_values(map!("temperature" => Value::number(current_temp)));
+        widget.set_values(map!("temperature" => Value::number(current_temp).with_slider()));
         api.set_widget(&widget).await?;
 
         select! {
@@ -125,29 +131,34 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             }
             event = api.event() => {
                 match event {
-                    Click(click) => match click.button {
-                        MouseButton::Left => {
-                            current_temp = config.click_temp;
-                            driver.update(current_temp).await?;
-                        }
-                        MouseButton::Right => {
-                            if max_temp > 6500 {
-                                current_temp = 6500;
-                                driver.reset().await?;
-                            } else {
-                                current_temp = max_temp;
+                    Click(click) => if let Some(temp) = click.slider_value() {
+                        current_temp = (temp.round() as u16).clamp(min_temp, max_temp);
+                        driver.update(current_temp).await?;
+                    } else {
+                        match click.button {
+                            MouseButton::Left => {
+                                current_temp = config.click_temp;
                                 driver.update(current_temp).await?;
                             }
+                            MouseButton::Right => {
+                                if max_temp > 6500 {
+                                    current_temp = 6500;
+                                    driver.reset().await?;
+                                } else {
+                                    current_temp = max_temp;
+                                    driver.update(current_temp).await?;
+                                }
+                            }
+                            MouseButton::WheelUp => {
+                                current_temp = (current_temp + step).min(max_temp);
+                                driver.update(current_temp).await?;
+                            }
+                            MouseButton::WheelDown => {
+                                current_temp = current_temp.saturating_sub(step).max(min_temp);
+                                driver.update(current_temp).await?;
+                            }
+                            _ => (),
                         }
-                        MouseButton::WheelUp => {
-                            current_temp = (current_temp + step).min(max_temp);
-                            driver.update(current_temp).await?;
-                        }
-                        MouseButton::WheelDown => {
-                            current_temp = current_temp.saturating_sub(step).max(min_temp);
-                            driver.update(current_temp).await?;
-                        }
-                        _ => (),
                     }
                     UpdateRequest => (),
                 }
@@ -40,16 +40,7 @@
 //!
 //! # Icons Used
 //! - `bat_charging`,
-//! - `bat_10`,
-//! - `bat_20`,
-//! - `bat_30`,
-//! - `bat_40`,
-//! - `bat_50`,
-//! - `bat_60`,
-//! - `bat_70`,
-//! - `bat_80`,
-//! - `bat_90`,
-//! - `bat_full`,
+//! - `bat` (an icon family from an empty to a full battery),
 //! - `notification`
 //! - `phone`
 //! - `phone_disconnected`
@@ -58,7 +49,17 @@ use tokio::sync::mpsc;
 use zbus::dbus_proxy;
 
 use super::prelude::*;
-use crate::util::battery_level_icon;
+use crate::util::battery_level_fraction;
+
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("bat_icon", ValueType::Icon),
+    ("bat_charge", ValueType::Number),
+    ("notif_icon", ValueType::Icon),
+    ("notif_count", ValueType::Number),
+    ("name", ValueType::Text),
+    ("connected", ValueType::Flag),
+];
 
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
@@ -122,7 +123,11 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                     values.insert("bat_charge".into(), Value::percents(level));
                     values.insert(
                         "bat_icon".into(),
-                        Value::icon(api.get_icon(battery_level_icon(level, charging))?),
+                        Value::icon(if charging {
+                            api.get_icon("bat_charging")?
+                        } else {
+                            api.get_icon_in_family("bat", battery_level_fraction(level as f64))?
+                        }),
                     );
                     if battery_state {
                         widget.state = if charging {
@@ -155,7 +160,11 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                     };
                 }
             } else {
-                values.insert("icon".into(), Value::icon(api.get_icon("phone_disconnected")?));
+                widget.state = State::Disconnected;
+                values.insert(
+                    "icon".into(),
+                    Value::icon(api.get_icon("phone_disconnected")?),
+                );
             }
 
             widget.set_values(values);
@@ -16,7 +16,7 @@
 //! `interval` | Update interval, in seconds. Only used by the `"setxkbmap"` driver. | `60`
 //! `format` | A string to customise the output of this block. See below for available placeholders. | `" $layout "`
 //! `sway_kb_identifier` | Identifier of the device you want to monitor, as found in the output of `swaymsg -t get_inputs`. | Defaults to first input found
-//! `mappings` | Map `layout (variant)` to custom short name. | `None`
+//! `mappings` | Map `layout (variant)` to custom short name. The [`map`](crate::formatting#map---map-values-to-other-values) formatter can be used instead. | `None`
 //!
 //!  Key     | Value | Type
 //! ---------|-------|-----
@@ -84,6 +84,9 @@ use swayipc_async::{Connection, Event, EventType};
 use tokio::process::Command;
 use zbus::dbus_proxy;
 
+pub const PLACEHOLDERS: &Placeholders =
+    &[("layout", ValueType::Text), ("variant", ValueType::Text)];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(default, deny_unknown_fields)]
 struct KeyboardLayoutConfig {
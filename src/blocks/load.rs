@@ -32,6 +32,13 @@
 use super::prelude::*;
 use crate::util;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("1m", ValueType::Number),
+    ("5m", ValueType::Number),
+    ("15m", ValueType::Number),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct LoadConfig {
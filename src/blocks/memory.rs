@@ -1,6 +1,7 @@
 //! Memory and swap usage
 //!
-//! This module keeps track of both Swap and Memory. By default, a click switches between them.
+//! This module keeps track of both Swap and Memory. `display_type` selects which of them is shown.
+//! To switch between them on click, set the common `format_alt` option (see the example).
 //!
 //! # Configuration
 //!
@@ -9,7 +10,6 @@
 //! `format_mem` | A string to customise the output of this block when in "Memory" view. See below for available placeholders. | `" $icon $mem_free.eng(3,B,M)/$mem_total.eng(3,B,M)($mem_total_used_percents.eng(2)) "`
 //! `format_swap` | A string to customise the output of this block when in "Swap" view. See below for available placeholders. | `" $icon $swap_free.eng(3,B,M)/$swap_total.eng(3,B,M)($swap_used_percents.eng(2)) "`
 //! `display_type` | Default view displayed on startup: "`memory`" or "`swap`" | `"memory"`
-//! `clickable` | Whether the view should switch between memory and swap on click | `true`
 //! `interval` | Update interval in seconds | `5`
 //! `warning_mem` | Percentage of memory usage, where state is set to warning | `80.0`
 //! `warning_swap` | Percentage of swap usage, where state is set to warning | `80.0`
@@ -44,12 +44,19 @@
 //! [[block]]
 //! block = "memory"
 //! format_mem = " $icon $mem_used_percents.eng(1) "
-//! clickable = false
 //! interval = 30
 //! warning_mem = 70
 //! critical_mem = 90
 //! ```
 //!
+//! Switch to the swap usage on click:
+//!
+//! ```toml
+//! [[block]]
+//! block = "memory"
+//! format_alt = " ^icon_memory_swap $swap_used_percents.eng(2) "
+//! ```
+//!
 //! # Icons Used
 //! - `memory_mem`
 //! - `memory_swap`
@@ -61,6 +68,28 @@ use tokio::io::{AsyncBufReadExt, BufReader};
 use super::prelude::*;
 use crate::util::read_file;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("mem_total", ValueType::Number),
+    ("mem_free", ValueType::Number),
+    ("mem_free_percents", ValueType::Number),
+    ("mem_total_used", ValueType::Number),
+    ("mem_total_used_percents", ValueType::Number),
+    ("mem_used", ValueType::Number),
+    ("mem_used_percents", ValueType::Number),
+    ("mem_avail", ValueType::Number),
+    ("mem_avail_percents", ValueType::Number),
+    ("swap_total", ValueType::Number),
+    ("swap_free", ValueType::Number),
+    ("swap_free_percents", ValueType::Number),
+    ("swap_used", ValueType::Number),
+    ("swap_used_percents", ValueType::Number),
+    ("buffers", ValueType::Number),
+    ("buffers_percent", ValueType::Number),
+    ("cached", ValueType::Number),
+    ("cached_percent", ValueType::Number),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct MemoryConfig {
@@ -68,8 +97,6 @@ struct MemoryConfig {
     format_swap: FormatConfig,
     #[default(Memtype::Memory)]
     display_type: Memtype,
-    #[default(true)]
-    clickable: bool,
     #[default(5.into())]
     interval: Seconds,
     #[default(80.0)]
@@ -93,16 +120,12 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         .format_swap
         .with_default(" $icon $swap_free.eng(3,B,M)/$swap_total.eng(3,B,M)($swap_used_percents.eng(2)) ")?;
 
-    let clickable = config.clickable;
-    let mut memtype = config.display_type;
-    let (mut icon, mut format) = match memtype {
-        Memtype::Memory => {
-            ("memory_mem", &format_mem)
-        }
-        Memtype::Swap => {
-            ("memory_swap", &format_swap)
-        }
+    let memtype = config.display_type;
+    let (icon, format) = match memtype {
+        Memtype::Memory => ("memory_mem", format_mem),
+        Memtype::Swap => ("memory_swap", format_swap),
     };
+    widget.set_format(format);
 
     let mut timer = config.interval.timer();
 
@@ -120,7 +143,6 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         let mem_used = mem_total_used - (buffers + cached);
         let mem_avail = mem_total - mem_used;
 
-        widget.set_format(format.clone());
         widget.set_values(map! {
             "icon" => Value::icon(api.get_icon(icon)?),
             "mem_total" => Value::bytes(mem_total),
@@ -158,30 +180,9 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
         api.set_widget(&widget).await?;
 
-        loop {
-            select! {
-                _ = timer.tick() => break,
-                event = api.event() => match event {
-                    UpdateRequest => break,
-                    Click(click) => {
-                        if click.button == MouseButton::Left && clickable {
-                            match memtype {
-                                Memtype::Swap => {
-                                    format = &format_mem;
-                                    memtype = Memtype::Memory;
-                                    icon = "memory_mem";
-                                }
-                                Memtype::Memory => {
-                                    format = &format_swap;
-                                    memtype = Memtype::Swap;
-                                    icon = "memory_swap";
-                                }
-                            }
-                            break;
-                        }
-                    }
-                }
-            }
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
         }
     }
 }
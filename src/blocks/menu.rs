@@ -34,6 +34,8 @@
 use super::prelude::*;
 use crate::subprocess::spawn_shell;
 
+pub const PLACEHOLDERS: &Placeholders = &[];
+
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 struct Config {
@@ -23,6 +23,7 @@
 //! `interface_name_exclude` | A list of regex patterns for player MPRIS interface names to ignore. | `[]`
 //! `separator` | String to insert between artist and title. | `" - "`
 //! `seek_step` | Number of microseconds to seek forward/backward when scrolling on the bar. | `1000`
+//! `progress_interval` | How often to refresh `$progress` while playing, in seconds. | `1`
 //! `hide_when_empty` | Hides the block when there is no player available. | `false`
 //!
 //! Note: All placeholders can be absent. See the examples below to learn how to handle this.
@@ -32,11 +33,13 @@
 //! `icon`      | A static icon  | Icon
 //! `artist`    | Current artist | Text
 //! `title`     | Current title  | Text
+//! `album`     | Current album  | Text
 //! `url`       | Current song url | Text
 //! `combo`     | Resolves to "`$artist[sep]$title"`, `"$artist"`, `"$title"`, or `"$url"` depending on what information is available. `[sep]` is set by `separator` option. | Text
 //! `player`    | Name of the current player (taken from the last part of its MPRIS bus name) | Text
 //! `avail`     | Total number of players available to switch between | Number
 //! `cur`       | Total number of players available to switch between | Number
+//! `progress`  | Playback position within the current track. When displayed as a bar, click on it to seek. | Number (%)
 //! `play`      | Play/Pause button | Clickable icon
 //! `next`      | Next button | Clickable icon
 //! `prev`      | Previous button | Clickable icon
@@ -62,6 +65,26 @@
 //! interface_name_exclude = [".*kdeconnect.*", "mpd"]
 //! ```
 //!
+//! Show the playback position as a bar, which can be clicked to seek:
+//!
+//! ```toml
+//! [[block]]
+//! block = "music"
+//! format = " $icon {$combo.str(0,20) $progress.bar(10) $play |}"
+//! ```
+//!
+//! Show the full title, artist, album and progress on the whole bar on middle click:
+//!
+//! ```toml
+//! [[block]]
+//! block = "music"
+//! format = " $icon {$combo.str(0,20) $play |}"
+//! format_expanded = " $icon {$title|}{ - $artist|}{ ($album)|} {$progress.bar(40) |}$prev $play $next "
+//! [[block.click]]
+//! button = "middle"
+//! expand = true
+//! ```
+//!
 //! Same as above, but displays with rotating text
 //!
 //! ```toml
@@ -83,7 +106,7 @@ use super::prelude::*;
 use regex::Regex;
 use zbus::fdo::DBusProxy;
 use zbus::names::{OwnedBusName, OwnedInterfaceName};
-use zbus::zvariant::{Optional, OwnedValue, Type};
+use zbus::zvariant::{OwnedObjectPath, Optional, OwnedValue, Type};
 use zbus::MessageStream;
 
 mod zbus_mpris;
@@ -94,6 +117,22 @@ const PLAY_PAUSE_BTN: usize = 1;
 const NEXT_BTN: usize = 2;
 const PREV_BTN: usize = 3;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("artist", ValueType::Text),
+    ("title", ValueType::Text),
+    ("album", ValueType::Text),
+    ("url", ValueType::Text),
+    ("combo", ValueType::Text),
+    ("player", ValueType::Text),
+    ("avail", ValueType::Number),
+    ("cur", ValueType::Number),
+    ("progress", ValueType::Number),
+    ("play", ValueType::Icon),
+    ("next", ValueType::Icon),
+    ("prev", ValueType::Icon),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct MusicConfig {
@@ -104,6 +143,8 @@ struct MusicConfig {
     separator: String,
     #[default(1_000)]
     seek_step: i64,
+    #[default(1.into())]
+    progress_interval: Seconds,
     hide_when_empty: bool,
 }
 
@@ -179,6 +220,9 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             .error( "failed to add match")?;
     let mut dbus_stream = MessageStream::from(&dbus_conn);
 
+    let show_progress = widget.format().is_some_and(|f| f.contains_key("progress"));
+    let mut progress_timer = config.progress_interval.timer();
+
     loop {
         debug!("available players:");
         for player in &players {
@@ -205,9 +249,15 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                     _ => (State::Idle, "music_play"),
                 };
                 values.insert("play".into(), new_btn(play_icon, PLAY_PAUSE_BTN, &mut api)?);
+                if let Some(progress) = player.progress().await {
+                    values.insert("progress".into(), Value::percents(progress).with_slider());
+                }
                 if let Some(url) = &player.url {
                     values.insert("url".into(), Value::text(url.clone()));
                 }
+                if let Some(album) = &player.album {
+                    values.insert("album".into(), Value::text(album.clone()));
+                }
                 match (&player.title, &player.artist, &player.url) {
                     (Some(t), None, _) => {
                         values.insert("combo".into(), Value::text(t.clone()));
@@ -244,7 +294,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             }
         }
 
+        let playing = cur_player.is_some_and(|i| players[i].status == Some(PlaybackStatus::Playing));
         select! {
+            // Refresh the playback position
+            _ = progress_timer.tick(), if playing && show_progress => (),
             // Wait for a DBUS event
             Some(msg) = dbus_stream.next() => {
                 let msg = msg.unwrap();
@@ -299,6 +352,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             // Wait for a click
             Click(click) = api.event() => {
                 if let Some(i) = cur_player {
+                    if let Some(progress) = click.slider_value() {
+                        players[i].set_progress(progress).await?;
+                        continue;
+                    }
                     match click.button {
                         MouseButton::Left => {
                             let player = &mut players[i];
@@ -364,7 +421,10 @@ struct Player {
     player_proxy: zbus_mpris::PlayerProxy<'static>,
     title: Option<String>,
     artist: Option<String>,
+    album: Option<String>,
     url: Option<String>,
+    track_id: Option<OwnedObjectPath>,
+    length: Option<i64>,
 }
 
 impl Player {
@@ -376,6 +436,8 @@ impl Player {
         let proxy = zbus_mpris::PlayerProxy::builder(dbus_conn)
             .destination(bus_name.clone())
             .error("failed to set proxy destination")?
+            // The position is not announced via `PropertiesChanged`
+            .uncached_properties(&["Position"])
             .build()
             .await
             .error("failed to open player proxy")?;
@@ -395,14 +457,39 @@ impl Player {
             player_proxy: proxy,
             title: metadata.title(),
             artist: metadata.artist(),
+            album: metadata.album(),
             url: metadata.url(),
+            track_id: metadata.track_id(),
+            length: metadata.length(),
         })
     }
 
     fn update_metadata(&mut self, metadata: zbus_mpris::PlayerMetadata) {
         self.title = metadata.title();
         self.artist = metadata.artist();
+        self.album = metadata.album();
         self.url = metadata.url();
+        self.track_id = metadata.track_id();
+        self.length = metadata.length();
+    }
+
+    /// Playback position in percent of the track length
+    async fn progress(&self) -> Option<f64> {
+        let length = self.length?;
+        let position = self.player_proxy.position().await.ok()?;
+        Some((position as f64 / length as f64 * 100.).clamp(0., 100.))
+    }
+
+    async fn set_progress(&self, progress: f64) -> Result<()> {
+        let (track_id, length) = match (&self.track_id, self.length) {
+            (Some(track_id), Some(length)) => (track_id, length),
+            _ => return Ok(()),
+        };
+        let position = (progress / 100. * length as f64) as i64;
+        self.player_proxy
+            .set_position(track_id, position)
+            .await
+            .error("set_position() failed")
     }
 
     async fn play_pause(&self) -> Result<()> {
@@ -21,7 +21,7 @@
 
 use std::collections::HashMap;
 use zbus::dbus_proxy;
-use zbus::zvariant::{ObjectPath, OwnedValue, Type};
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Type};
 
 #[derive(Debug, Clone, Type)]
 pub struct PlayerMetadata(pub HashMap<String, OwnedValue>);
@@ -50,10 +50,36 @@ impl PlayerMetadata {
         (!artist.is_empty()).then(|| artist.into())
     }
 
+    pub fn album(&self) -> Option<String> {
+        let album = self.0.get("xesam:album")?.downcast_ref::<str>()?;
+        (!album.is_empty()).then(|| album.into())
+    }
+
     pub fn url(&self) -> Option<String> {
         let url = self.0.get("xesam:url")?.downcast_ref::<str>()?;
         (!url.is_empty()).then(|| url.into())
     }
+
+    /// Track length in microseconds
+    pub fn length(&self) -> Option<i64> {
+        let length = self.0.get("mpris:length")?;
+        length
+            .downcast_ref::<i64>()
+            .copied()
+            .or_else(|| length.downcast_ref::<u64>().map(|&l| l as i64))
+            .filter(|&l| l > 0)
+    }
+
+    pub fn track_id(&self) -> Option<OwnedObjectPath> {
+        let id = self.0.get("mpris:trackid")?;
+        match id.downcast_ref::<ObjectPath>() {
+            Some(path) => Some(path.clone().into()),
+            // Some players send the track id as a string
+            None => ObjectPath::try_from(id.downcast_ref::<str>()?)
+                .ok()
+                .map(Into::into),
+        }
+    }
 }
 
 #[dbus_proxy(
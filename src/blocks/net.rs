@@ -7,26 +7,29 @@
 //! Key | Values | Default
 //! ----|--------|--------
 //! `device` | Network interface to monitor (as specified in `/sys/class/net/`). Supports regex. | If not set, device will be automatically selected every `interval`
-//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon ^icon_net_down $speed_down.eng(3,B,K) ^icon_net_up $speed_up.eng(3,B,K) "`
-//! `format_alt` | If set, block will switch between `format` and `format_alt` on every click | `None`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon ^icon_net_down $speed_down.eng(3,B/s,K) ^icon_net_up $speed_up.eng(3,B/s,K) "`
 //! `interval` | Update interval in seconds | `2`
 //! `missing_format` | Same as `format` if the interface cannot be connected (or missing). | `" × "`
 //!
 //! Placeholder       | Value                       | Type   | Unit
 //! ------------------|-----------------------------|--------|---------------
 //! `icon`            | Icon based on device's type | Icon   | -
-//! `speed_down`      | Download speed              | Number | Bytes per second
-//! `speed_up`        | Upload speed                | Number | Bytes per second
+//! `speed_down`      | Download speed              | Number | Bytes per second (`B/s`)
+//! `speed_up`        | Upload speed                | Number | Bytes per second (`B/s`)
 //! `graph_down`      | Download speed graph        | Text   | -
 //! `graph_up`        | Upload speed graph          | Text   | -
 //! `device`          | The name of device          | Text   | -
 //! `ssid`            | Netfork SSID (WiFi only)    | Text   | -
 //! `frequency`       | WiFi frequency              | Number | Hz
 //! `signal_strength` | WiFi signal                 | Number | %
-//! `bitrate`         | WiFi connection bitrate     | Number | Bits per second
+//! `bitrate`         | WiFi connection bitrate     | Number | Bits per second (`b/s`)
 //! `ip`              | IPv4 address of the iface   | Text   | -
 //! `ipv6`            | IPv6 address of the iface   | Text   | -
 //!
+//! Note that `graph_down` and `graph_up` are always 8 characters long. For a configurable graph
+//! use the [`graph`](crate::formatting#graph---display-the-history-of-a-number-as-a-sparkline)
+//! formatter, e.g. `$speed_down.graph(20)`.
+//!
 //! # Example
 //!
 //! Display WiFi info if available
@@ -59,12 +62,26 @@ use crate::util;
 use regex::Regex;
 use std::time::Instant;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("speed_down", ValueType::Number),
+    ("speed_up", ValueType::Number),
+    ("graph_down", ValueType::Text),
+    ("graph_up", ValueType::Text),
+    ("device", ValueType::Text),
+    ("ssid", ValueType::Text),
+    ("frequency", ValueType::Number),
+    ("signal_strength", ValueType::Number),
+    ("bitrate", ValueType::Number),
+    ("ip", ValueType::Text),
+    ("ipv6", ValueType::Text),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct NetConfig {
     device: Option<String>,
     format: FormatConfig,
-    format_alt: Option<FormatConfig>,
     missing_format: FormatConfig,
     #[default(2.into())]
     interval: Seconds,
@@ -73,14 +90,10 @@ struct NetConfig {
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = NetConfig::deserialize(config).config_error()?;
 
-    let mut format = config.format.with_default(
-        " $icon ^icon_net_down $speed_down.eng(3,B,K) ^icon_net_up $speed_up.eng(3,B,K) ",
+    let format = config.format.with_default(
+        " $icon ^icon_net_down $speed_down.eng(3,B/s,K) ^icon_net_up $speed_up.eng(3,B/s,K) ",
     )?;
     let missing_format = config.missing_format.with_default(" × ")?;
-    let mut format_alt = match config.format_alt {
-        Some(f) => Some(f.with_default("")?),
-        None => None,
-    };
 
     let mut widget = api.new_widget().with_format(format.clone());
     let mut timer = config.interval.timer();
@@ -101,11 +114,11 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     loop {
         match NetDevice::new(device_re.as_ref()).await? {
             None => {
-                widget.set_format(missing_format.clone());
+                widget.set_state_format(missing_format.clone());
                 api.set_widget(&widget).await?;
             }
             Some(device) if !device.iface.is_up => {
-                widget.set_format(missing_format.clone());
+                widget.set_state_format(missing_format.clone());
                 api.set_widget(&widget).await?;
             }
             Some(device) => {
@@ -135,15 +148,15 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
                 let values = map! {
                     "icon" => Value::icon(api.get_icon(device.icon)?),
-                    "speed_down" => Value::bytes(speed_down),
-                    "speed_up" => Value::bytes(speed_up),
+                    "speed_down" => Value::bytes_per_second(speed_down),
+                    "speed_up" => Value::bytes_per_second(speed_up),
                     "graph_down" => Value::text(util::format_bar_graph(&rx_hist)),
                     "graph_up" => Value::text(util::format_bar_graph(&tx_hist)),
                     [if let Some(v) = device.ip] "ip" => Value::text(v.to_string()),
                     [if let Some(v) = device.ipv6] "ipv6" => Value::text(v.to_string()),
                     [if let Some(v) = device.ssid()] "ssid" => Value::text(v),
                     [if let Some(v) = device.frequency()] "frequency" => Value::hertz(v),
-                    [if let Some(v) = device.bitrate()] "bitrate" => Value::bits(v),
+                    [if let Some(v) = device.bitrate()] "bitrate" => Value::bits_per_second(v),
                     [if let Some(v) = device.signal()] "signal_strength" => Value::percents(v),
                     "device" => Value::text(device.iface.name),
                 };
@@ -153,21 +166,9 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             }
         }
 
-        loop {
-            select! {
-                _ = timer.tick() => break,
-                event = api.event() => match event {
-                    UpdateRequest => break,
-                    Click(click) => {
-                        if click.button == MouseButton::Left {
-                            if let Some(format_alt) = &mut format_alt {
-                                std::mem::swap(format_alt, &mut format);
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
+        select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
         }
     }
 }
@@ -37,6 +37,8 @@ use zbus::PropertyStream;
 const ICON_ON: &str = "bell";
 const ICON_OFF: &str = "bell-slash";
 
+pub const PLACEHOLDERS: &Placeholders = &[("icon", ValueType::Icon), ("paused", ValueType::Flag)];
+
 #[derive(Deserialize, Debug, Default)]
 #[serde(deny_unknown_fields, default)]
 struct NotifyConfig {
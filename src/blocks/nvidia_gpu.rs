@@ -28,7 +28,7 @@
 //! `name`        | Text   | -
 //! `utilization` | Number | Percents
 //! `memory`      | Number | Bytes
-//! `temperature` | Number | Degrees
+//! `temperature` | Number | Degrees Celsius
 //! `fan_speed`   | Number | Percents
 //! `clocks`      | Number | Hertz
 //! `power`       | Number | Watts
@@ -60,6 +60,18 @@ const QUERY: &str = "--query-gpu=name,memory.total,utilization.gpu,memory.used,t
 const FORMAT: &str = "--format=csv,noheader,nounits";
 
 use super::prelude::*;
+use crate::subprocess::run_command_stdout;
+
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("name", ValueType::Text),
+    ("utilization", ValueType::Number),
+    ("memory", ValueType::Number),
+    ("temperature", ValueType::Number),
+    ("fan_speed", ValueType::Number),
+    ("clocks", ValueType::Number),
+    ("power", ValueType::Number),
+];
 
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
@@ -122,7 +134,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             "name" => Value::text(info.name.clone()),
             "utilization" => Value::percents(info.utilization),
             "memory" => Value::bytes(if show_mem_total {info.mem_total} else {info.mem_used}).with_instance(MEM_BTN),
-            "temperature" => Value::degrees(info.temperature),
+            "temperature" => Value::celsius(info.temperature),
             "fan_speed" => Value::percents(info.fan_speed).with_instance(FAN_BTN).underline(fan_controlled).italic(fan_controlled),
             "clocks" => Value::hertz(info.clocks),
             "power" => Value::watts(info.power_draw),
@@ -142,17 +154,17 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                         Some(FAN_BTN ) => match click.button {
                             MouseButton::Left => {
                                 fan_controlled = !fan_controlled;
-                                set_fan_speed(config.gpu_id, fan_controlled.then_some(info.fan_speed)).await?;
+                                set_fan_speed(config.gpu_id, fan_controlled.then_some(info.fan_speed), api.command_timeout).await?;
                                 break;
                             }
                             MouseButton::WheelUp if fan_controlled && info.fan_speed < 100 => {
                                 info.fan_speed += 1;
-                                set_fan_speed(config.gpu_id, Some(info.fan_speed)).await?;
+                                set_fan_speed(config.gpu_id, Some(info.fan_speed), api.command_timeout).await?;
                                 break;
                             }
                             MouseButton::WheelDown if fan_controlled && info.fan_speed > 0 => {
                                 info.fan_speed -= 1;
-                                set_fan_speed(config.gpu_id, Some(info.fan_speed)).await?;
+                                set_fan_speed(config.gpu_id, Some(info.fan_speed), api.command_timeout).await?;
                                 break;
                             }
                             _ => (),
@@ -231,8 +243,7 @@ impl FromStr for GpuInfo {
     }
 }
 
-async fn set_fan_speed(id: u64, speed: Option<u32>) -> Result<()> {
-    const ERR_MSG: &str = "Failed to execute nvidia-settings";
+async fn set_fan_speed(id: u64, speed: Option<u32>, timeout: Duration) -> Result<()> {
     let mut cmd = Command::new("nvidia-settings");
     if let Some(speed) = speed {
         cmd.args([
@@ -244,16 +255,6 @@ async fn set_fan_speed(id: u64, speed: Option<u32>) -> Result<()> {
     } else {
         cmd.args(["-a", &format!("[gpu:{id}]/GPUFanControlState=0")]);
     }
-    if cmd
-        .spawn()
-        .error(ERR_MSG)?
-        .wait()
-        .await
-        .error(ERR_MSG)?
-        .success()
-    {
-        Ok(())
-    } else {
-        Err(Error::new(ERR_MSG))
-    }
+    run_command_stdout(&mut cmd, timeout).await?;
+    Ok(())
 }
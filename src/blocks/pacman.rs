@@ -41,11 +41,10 @@
 //! ----|--------|---------
 //! `interval` | Update interval, in seconds. If setting `aur_command` then set interval appropriately as to not exceed the AUR's daily rate limit. | `600`
 //! `format` | A string to customise the output of this block. See below for available placeholders. | `" $icon $pacman.eng(1) "`
-//! `format_singular` | Same as `format` but for when exactly one update is available. | `" $icon $pacman.eng(1) "`
 //! `format_up_to_date` | Same as `format` but for when no updates are available. | `" $icon $pacman.eng(1) "`
 //! `warning_updates_regex` | Display block as warning if updates matching regex are available. | `None`
 //! `critical_updates_regex` | Display block as critical if updates matching regex are available. | `None`
-//! `aur_command` | AUR command to check available updates, which outputs in the same format as pacman. e.g. `yay -Qua` | Required if `$both` or `$aur` are used
+//! `aur_command` | AUR command to check available updates, which outputs in the same format as pacman. e.g. `yay -Qua`. If it exits with a non-zero status, its stderr is shown as an error, so helpers which exit with 1 when there are no updates need e.g. `yay -Qua \|\| true` | Required if `$both` or `$aur` are used
 //!
 //!  Placeholder | Value | Type | Unit
 //! -------------|----------------------------------------------------------------------------------|--------|-----
@@ -53,6 +52,15 @@
 //! `pacman`     | Number of updates available according to `pacman`                                | Number | -
 //! `aur`        | Number of updates available according to `<aur_command>`                         | Number | -
 //! `both`       | Cumulative number of updates available according to `pacman` and `<aur_command>` | Number | -
+//! `updates`    | Every available update, see below                                                | List   | -
+//!
+//! Each item of `updates` has the following placeholders:
+//!
+//!  Placeholder  | Value                        | Type
+//! --------------|------------------------------|------
+//! `name`        | Name of the package          | Text
+//! `old_version` | Currently installed version  | Text
+//! `new_version` | Available version            | Text
 //!
 //! # Examples
 //!
@@ -63,7 +71,6 @@
 //! block = "pacman"
 //! interval = 600
 //! format = " $icon $pacman updates available "
-//! format_singular = " $icon $pacman update available "
 //! format_up_to_date = " $icon system up to date "
 //! critical_updates_regex = "(linux|linux-lts|linux-zen)"
 //! [[block.click]]
@@ -79,7 +86,6 @@
 //! block = "pacman"
 //! interval = 600
 //! format = " $icon $pacman updates available "
-//! format_singular = " $icon $pacman update available "
 //! format_up_to_date = " $icon system up to date "
 //! # If a linux update is availble, but no ZFS package, it won't be possible to
 //! # actually perform a system upgrade, so we show a warning.
@@ -95,21 +101,30 @@
 //! [[block]]
 //! block = "pacman"
 //! interval = 600
-//! format = " $icon $pacman + $aur = $both updates available "
-//! format_singular = " $icon $both update available "
+//! format = " $icon $pacman + $aur = $both $both.plural(update, updates) available "
 //! format_up_to_date = " $icon system up to date "
 //! critical_updates_regex = "(linux|linux-lts|linux-zen)"
 //! # aur_command should output available updates to stdout (ie behave as echo -ne "update\n")
 //! aur_command = "yay -Qua"
 //! ```
 //!
+//! Show the list of updates when the block is clicked:
+//!
+//! ```toml
+//! [[block]]
+//! block = "pacman"
+//! format_expanded = " $updates{$name $old_version → $new_version}.join(\", \") "
+//! [[block.click]]
+//! button = "right"
+//! expand = true
+//! ```
+//!
 //! # Icons Used
 //!
 //! - `update`
 
 use std::env;
 use std::path::PathBuf;
-use std::process::Stdio;
 
 use regex::Regex;
 
@@ -117,6 +132,7 @@ use tokio::fs::{create_dir_all, symlink};
 use tokio::process::Command;
 
 use super::prelude::*;
+use crate::subprocess::{run_command, run_command_stdout};
 use crate::util::has_command;
 
 make_log_macro!(debug, "pacman");
@@ -143,13 +159,27 @@ static PACMAN_DB: Lazy<PathBuf> = Lazy::new(|| {
     path
 });
 
+/// Placeholders of each item of `updates`
+const UPDATE_PLACEHOLDERS: &Placeholders = &[
+    ("name", ValueType::Text),
+    ("old_version", ValueType::Text),
+    ("new_version", ValueType::Text),
+];
+
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("pacman", ValueType::Number),
+    ("aur", ValueType::Number),
+    ("both", ValueType::Number),
+    ("updates", ValueType::List(UPDATE_PLACEHOLDERS)),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct PacmanConfig {
     #[default(600.into())]
     interval: Seconds,
     format: FormatConfig,
-    format_singular: FormatConfig,
     format_up_to_date: FormatConfig,
     warning_updates_regex: Option<String>,
     critical_updates_regex: Option<String>,
@@ -161,13 +191,11 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut widget = api.new_widget();
 
     let format = config.format.with_default(" $icon $pacman.eng(1) ")?;
-    let format_singular = config.format_singular.with_default(" $icon $pacman.eng(1) ")?;
     let format_up_to_date = config.format_up_to_date.with_default(" $icon $pacman.eng(1) ")?;
 
     macro_rules! any_format_contains {
         ($name:expr) => {
             format.contains_key($name)
-                || format_singular.contains_key($name)
                 || format_up_to_date.contains_key($name)
         };
     }
@@ -209,12 +237,19 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         .transpose()
         .error("invalid critical updates regex")?;
 
+    let timeout = api.command_timeout;
+
     loop {
         let (mut values, warning, critical, total) = match &watched {
             Watched::Pacman => {
-                let updates = api.recoverable(get_pacman_available_updates).await?;
+                let updates = api
+                    .recoverable(|| get_pacman_available_updates(timeout))
+                    .await?;
                 let count = get_update_count(&updates);
-                let values = map!("pacman" => Value::number(count));
+                let values = map!(
+                    "pacman" => Value::number(count),
+                    "updates" => get_updates_list([&updates]),
+                );
                 let warning = warning_updates_regex
                     .as_ref()
                     .map_or(false, |regex| has_matching_update(&updates, regex));
@@ -225,11 +260,12 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             }
             Watched::Aur(aur_command) => {
                 let updates = api
-                    .recoverable(|| get_aur_available_updates(aur_command))
+                    .recoverable(|| get_aur_available_updates(aur_command, timeout))
                     .await?;
                 let count = get_update_count(&updates);
                 let values = map!(
-                    "aur" => Value::number(count)
+                    "aur" => Value::number(count),
+                    "updates" => get_updates_list([&updates]),
                 );
                 let warning = warning_updates_regex
                     .as_ref()
@@ -243,8 +279,8 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 let (pacman_updates, aur_updates) = api
                     .recoverable(|| async {
                         tokio::try_join!(
-                            get_pacman_available_updates(),
-                            get_aur_available_updates(aur_command)
+                            get_pacman_available_updates(timeout),
+                            get_aur_available_updates(aur_command, timeout)
                         )
                     })
                     .await?;
@@ -254,6 +290,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                     "pacman" => Value::number(pacman_count),
                     "aur" =>    Value::number(aur_count),
                     "both" =>   Value::number(pacman_count + aur_count),
+                    "updates" => get_updates_list([&pacman_updates, &aur_updates]),
                 };
                 let warning = warning_updates_regex.as_ref().map_or(false, |regex| {
                     has_matching_update(&aur_updates, regex)
@@ -269,11 +306,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         };
         values.insert("icon".into(),  Value::icon(api.get_icon("update")?));
 
-        widget.set_format(match total {
-            0 => format_up_to_date.clone(),
-            1 => format_singular.clone(),
-            _ => format.clone(),
-        });
+        match total {
+            0 => widget.set_state_format(format_up_to_date.clone()),
+            _ => widget.set_format(format.clone()),
+        }
         widget.set_values(values);
         widget.state = match total {
             0 => State::Idle,
@@ -321,7 +357,7 @@ async fn check_fakeroot_command_exists() -> Result<()> {
     }
 }
 
-async fn get_pacman_available_updates() -> Result<String> {
+async fn get_pacman_available_updates(timeout: Duration) -> Result<String> {
     // Create the determined `checkup-db` path recursively
     create_dir_all(&*PACMAN_UPDATES_DB).await.or_error(|| {
         format!(
@@ -339,9 +375,8 @@ async fn get_pacman_available_updates() -> Result<String> {
     }
 
     // Update database
-    let status = Command::new("fakeroot")
-        .env("LC_ALL", "C")
-        .args([
+    run_command_stdout(
+        Command::new("fakeroot").env("LC_ALL", "C").args([
             "--".as_ref(),
             "pacman".as_ref(),
             "-Sy".as_ref(),
@@ -349,41 +384,30 @@ async fn get_pacman_available_updates() -> Result<String> {
             PACMAN_UPDATES_DB.as_os_str(),
             "--logfile".as_ref(),
             "/dev/null".as_ref(),
-        ])
-        .stdout(Stdio::null())
-        .status()
-        .await
-        .error("Failed to run command")?;
-    if !status.success() {
-        return Err(Error::new("pacman -Sy exited with non zero exit status"));
-    }
+        ]),
+        timeout,
+    )
+    .await?;
 
-    let stdout = Command::new("fakeroot")
-        .env("LC_ALL", "C")
-        .args([
+    // `pacman -Qu` exits with 1 if there are no updates
+    let stdout = run_command(
+        Command::new("fakeroot").env("LC_ALL", "C").args([
             "--".as_ref(),
             "pacman".as_ref(),
             "-Qu".as_ref(),
             "--dbpath".as_ref(),
             PACMAN_UPDATES_DB.as_os_str(),
-        ])
-        .output()
-        .await
-        .error("There was a problem running the pacman commands")?
-        .stdout;
+        ]),
+        timeout,
+    )
+    .await?
+    .stdout;
 
     String::from_utf8(stdout).error("Pacman produced non-UTF8 output")
 }
 
-async fn get_aur_available_updates(aur_command: &str) -> Result<String> {
-    let stdout = Command::new("sh")
-        .args(["-c", aur_command])
-        .output()
-        .await
-        .or_error(|| format!("aur command: {aur_command} failed"))?
-        .stdout;
-    String::from_utf8(stdout)
-        .error("There was a problem while converting the aur command output to a string")
+async fn get_aur_available_updates(aur_command: &str, timeout: Duration) -> Result<String> {
+    run_command_stdout(Command::new("sh").args(["-c", aur_command]), timeout).await
 }
 
 fn get_update_count(updates: &str) -> usize {
@@ -393,6 +417,28 @@ fn get_update_count(updates: &str) -> usize {
         .count()
 }
 
+/// The updates as a list of `name old_version -> new_version` items
+fn get_updates_list<'a>(outputs: impl IntoIterator<Item = &'a String>) -> Value {
+    Value::list(
+        outputs
+            .into_iter()
+            .flat_map(|updates| updates.lines())
+            .filter(|line| !line.contains("[ignored]"))
+            .filter_map(|line| {
+                let mut words = line.split_whitespace();
+                let name = words.next()?;
+                let old_version = words.next().unwrap_or_default();
+                let new_version = words.nth(1).unwrap_or_default();
+                Some(map! {
+                    "name" => Value::text(name.into()),
+                    "old_version" => Value::text(old_version.into()),
+                    "new_version" => Value::text(new_version.into()),
+                })
+            })
+            .collect(),
+    )
+}
+
 fn has_matching_update(updates: &str, regex: &Regex) -> bool {
     updates.lines().any(|line| regex.is_match(line))
 }
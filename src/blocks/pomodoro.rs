@@ -61,6 +61,8 @@ use super::prelude::*;
 use crate::subprocess::{spawn_shell, spawn_shell_sync};
 use std::time::Instant;
 
+pub const PLACEHOLDERS: &Placeholders = &[("icon", ValueType::Icon), ("message", ValueType::Text)];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct PomodoroConfig {
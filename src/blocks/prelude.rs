@@ -2,10 +2,14 @@ pub use super::{BlockEvent::*, CommonApi};
 
 pub use crate::click::MouseButton;
 pub use crate::errors::*;
-pub use crate::formatting::{config::Config as FormatConfig, value::Value, Values};
+pub use crate::formatting::{
+    config::Config as FormatConfig,
+    value::{Value, ValueType},
+    Placeholders, Values,
+};
 pub use crate::util::{default, new_dbus_connection, new_system_dbus_connection};
 pub use crate::widget::{State, Widget};
-pub use crate::wrappers::{Seconds, ShellString};
+pub use crate::wrappers::{Seconds, Secret, ShellString};
 pub use crate::REQWEST_CLIENT;
 
 pub use serde::Deserialize;
@@ -32,6 +32,8 @@ use tokio::net::UnixStream;
 use super::prelude::*;
 use crate::subprocess::spawn_shell;
 
+pub const PLACEHOLDERS: &Placeholders = &[("icon", ValueType::Icon), ("num", ValueType::Number)];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct RoficationConfig {
@@ -1,6 +1,6 @@
 //! Volume level
 //!
-//! This block displays the volume level (according to PulseAudio or ALSA). Right click to toggle mute, scroll to adjust volume.
+//! This block displays the volume level (according to PulseAudio or ALSA). Right click to toggle mute, scroll to adjust volume. If `$volume` is displayed as a bar, clicking on the bar sets the volume.
 //!
 //! Requires a PulseAudio installation or `alsa-utils` for ALSA.
 //!
@@ -22,6 +22,14 @@
 //! format = " $icon $output_description{ $volume|} "
 //! ```
 //!
+//! Show the volume as a slider which can be clicked to set the volume:
+//!
+//! ```toml
+//! [[block]]
+//! block = "sound"
+//! format = " $icon {$volume.bar(10) $volume|} "
+//! ```
+//!
 //! ```toml
 //! [[block]]
 //! block = "sound"
@@ -45,7 +53,7 @@
 //! `max_vol` | Max volume in percent that can be set via scrolling. Note it can still be set above this value if changed by another application. | `None`
 //! `show_volume_when_muted` | Show the volume even if it is currently muted. | `false`
 //! `headphones_indicator` | Change icon when headphones are plugged in (pulseaudio only) | `false`
-//! `mappings` | Map `output_name` to custom name. | `None`
+//! `mappings` | Map `output_name` to custom name. The [`map`](crate::formatting#map---map-values-to-other-values) formatter can be used instead. | `None`
 //!
 //! Placeholder          | Value                             | Type   | Unit
 //! ---------------------|-----------------------------------|--------|---------------
@@ -57,13 +65,9 @@
 //! #  Icons Used
 //!
 //! - `microphone_muted`
-//! - `microphone_empty` (1 to 20%)
-//! - `microphone_half` (21 to 70%)
-//! - `microphone_full` (over 71%)
+//! - `microphone` (an icon family from the lowest to the highest volume)
 //! - `volume_muted`
-//! - `volume_empty` (1 to 20%)
-//! - `volume_half` (21 to 70%)
-//! - `volume_full` (over 71%)
+//! - `volume` (an icon family from the lowest to the highest volume)
 //! - `headphones`
 
 mod alsa;
@@ -72,6 +76,13 @@ mod pulseaudio;
 
 use super::prelude::*;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("volume", ValueType::Number),
+    ("output_name", ValueType::Text),
+    ("output_description", ValueType::Text),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct SoundConfig {
@@ -98,7 +109,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let device_kind = config.device_kind;
     let step_width = config.step_width.clamp(0, 50) as i32;
 
-    let icon = |volume: u32, device: &dyn SoundDevice| -> String {
+    let icon = |api: &CommonApi, volume: u32, device: &dyn SoundDevice| -> Result<String> {
         if config.headphones_indicator && device_kind == DeviceKind::Sink {
             let headphones = match device.form_factor() {
                 // form_factor's possible values are listed at:
@@ -115,23 +126,19 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 _ => false,
             };
             if headphones {
-                return "headphones".into();
+                return api.get_icon("headphones");
             }
         }
 
-        format!(
-            "{}_{}",
-            match device_kind {
-                DeviceKind::Source => "microphone",
-                DeviceKind::Sink => "volume",
-            },
-            match volume {
-                0 => "muted",
-                1..=20 => "empty",
-                21..=70 => "half",
-                _ => "full",
-            }
-        )
+        let family = match device_kind {
+            DeviceKind::Source => "microphone",
+            DeviceKind::Sink => "volume",
+        };
+        if volume == 0 {
+            api.get_icon(&format!("{family}_muted"))
+        } else {
+            api.get_icon_in_family(family, volume as f64 / 100.)
+        }
     };
 
     type DeviceType = Box<dyn SoundDevice>;
@@ -181,19 +188,19 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             .unwrap_or_else(|| output_name.clone());
 
         let mut values = map! {
-            "volume" => Value::percents(volume),
+            "volume" => Value::percents(volume).with_slider(),
             "output_name" => Value::text(output_name),
             "output_description" => Value::text(output_description),
         };
 
         if device.muted() {
-            values.insert("icon".into(), Value::icon(api.get_icon(&icon(0, &*device))?));
-            widget.state = State::Warning;
+            values.insert("icon".into(), Value::icon(icon(&api, 0, &*device)?));
+            widget.state = State::Muted;
             if !config.show_volume_when_muted {
                 values.remove("volume");
             }
         } else {
-            values.insert("icon".into(), Value::icon(api.get_icon(&icon(volume, &*device))?));
+            values.insert("icon".into(), Value::icon(icon(&api, volume, &*device)?));
             widget.state = State::Idle;
         }
 
@@ -208,17 +215,22 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 }
                 event = api.event() => match event {
                     UpdateRequest => break,
-                    Click(click) => match click.button {
-                        MouseButton::Right => {
-                            device.toggle().await?;
-                        }
-                        MouseButton::WheelUp => {
-                            device.set_volume(step_width, config.max_vol).await?;
-                        }
-                        MouseButton::WheelDown => {
-                            device.set_volume(-step_width, config.max_vol).await?;
+                    Click(click) => if let Some(target) = click.slider_value() {
+                        let step = target.round() as i32 - device.volume() as i32;
+                        device.set_volume(step, config.max_vol).await?;
+                    } else {
+                        match click.button {
+                            MouseButton::Right => {
+                                device.toggle().await?;
+                            }
+                            MouseButton::WheelUp => {
+                                device.set_volume(step_width, config.max_vol).await?;
+                            }
+                            MouseButton::WheelDown => {
+                                device.set_volume(-step_width, config.max_vol).await?;
+                            }
+                            _ => ()
                         }
-                        _ => ()
                     }
                 }
             }
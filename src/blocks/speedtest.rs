@@ -12,8 +12,8 @@
 //! Placeholder  | Value          | Type   | Unit
 //! -------------|----------------|--------|---------------
 //! `ping`       | Ping delay     | Number | Seconds
-//! `speed_down` | Download speed | Number | Bits per second
-//! `speed_up`   | Upload speed   | Number | Bits per second
+//! `speed_down` | Download speed | Number | Bits per second (`b/s`)
+//! `speed_up`   | Upload speed   | Number | Bits per second (`b/s`)
 //!
 //! # Example
 //!
@@ -32,7 +32,7 @@
 //! [[block]]
 //! block = "speedtest"
 //! interval = 1800
-//! format = " $speed_down.eng(4,B) $speed_up(4,B) "
+//! format = " $speed_down.eng(4,B/s) $speed_up.eng(4,B/s) "
 //! ```
 //!
 //! # Icons Used
@@ -41,8 +41,15 @@
 //! - `net_up`
 
 use super::prelude::*;
+use crate::subprocess::run_command_stdout;
 use tokio::process::Command;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("ping", ValueType::Number),
+    ("speed_down", ValueType::Number),
+    ("speed_up", ValueType::Number),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct SpeedtestConfig {
@@ -62,20 +69,14 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     command.arg("--json");
 
     loop {
-        let output = command
-            .output()
-            .await
-            .error("failed to run 'speedtest-cli'")?
-            .stdout;
-        let output =
-            std::str::from_utf8(&output).error("'speedtest-cli' produced non-UTF8 outupt")?;
+        let output = run_command_stdout(&mut command, api.command_timeout).await?;
         let output: SpeedtestCliOutput =
-            serde_json::from_str(output).error("'speedtest-cli' produced wrong JSON")?;
+            serde_json::from_str(&output).error("'speedtest-cli' produced wrong JSON")?;
 
         widget.set_values(map! {
             "ping" => Value::seconds(output.ping * 1e-3),
-            "speed_down" => Value::bits(output.download),
-            "speed_up" => Value::bits(output.upload),
+            "speed_down" => Value::bits_per_second(output.download),
+            "speed_up" => Value::bits_per_second(output.upload),
         });
         api.set_widget(&widget).await?;
 
@@ -46,9 +46,18 @@
 //! - `tasks`
 
 use super::prelude::*;
+use crate::subprocess::run_command_stdout;
 use inotify::{Inotify, WatchMask};
 use tokio::process::Command;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("count", ValueType::Number),
+    ("filter_name", ValueType::Text),
+    ("done", ValueType::Flag),
+    ("single", ValueType::Flag),
+];
+
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields, default)]
 struct TaskwarriorConfig {
@@ -96,7 +105,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         .error("Failed to create event stream")?;
 
     loop {
-        let number_of_tasks = get_number_of_tasks(&filter.filter).await?;
+        let number_of_tasks = get_number_of_tasks(&filter.filter, api.command_timeout).await?;
 
         if number_of_tasks != 0 || !config.hide_when_zero {
             widget.set_values(map! {
@@ -135,18 +144,15 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     }
 }
 
-async fn get_number_of_tasks(filter: &str) -> Result<u32> {
-    let output = Command::new("task")
-        .args(["rc.gc=off", filter, "count"])
-        .output()
-        .await
-        .error("failed to run taskwarrior for getting the number of tasks")?
-        .stdout;
-    std::str::from_utf8(&output)
-        .error("failed to get the number of tasks from taskwarrior (invalid UTF-8)")?
-        .trim()
-        .parse::<u32>()
-        .error("could not parse the result of taskwarrior")
+async fn get_number_of_tasks(filter: &str, timeout: Duration) -> Result<u32> {
+    run_command_stdout(
+        Command::new("task").args(["rc.gc=off", filter, "count"]),
+        timeout,
+    )
+    .await?
+    .trim()
+    .parse::<u32>()
+    .error("could not parse the result of taskwarrior")
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
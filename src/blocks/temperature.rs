@@ -21,7 +21,7 @@
 //! `format` | A string to customise the output of this block. See below for available placeholders | <code>" $icon{ $average avg, $max max&vert;} "</code>
 //! `interval` | Update interval in seconds | `5`
 //! `collapsed` | Whether the block will be collapsed by default | `true`
-//! `scale` | Either `"celsius"` or `"fahrenheit"` | `"celsius"`
+//! `scale` | Either `"celsius"` or `"fahrenheit"`. Applies to the thresholds below and to the placeholders, which can still be converted in `format`, e.g. `$max.eng(2,K)` | `"celsius"`
 //! `good` | Maximum temperature to set state to good | `20` °C (`68` °F)
 //! `idle` | Maximum temperature to set state to idle | `45` °C (`113` °F)
 //! `info` | Maximum temperature to set state to info | `60` °C (`140` °F)
@@ -31,9 +31,18 @@
 //!
 //! Placeholder | Value                                | Type   | Unit
 //! ------------|--------------------------------------|--------|--------
-//! `min`       | Minimum temperature among all inputs | Number | Degrees
-//! `average`   | Average temperature among all inputs | Number | Degrees
-//! `max`       | Maximum temperature among all inputs | Number | Degrees
+//! `min`       | Minimum temperature among all inputs | Number | Degrees in `scale`
+//! `average`   | Average temperature among all inputs | Number | Degrees in `scale`
+//! `max`       | Maximum temperature among all inputs | Number | Degrees in `scale`
+//! `sensors`   | Every input, see below               | List   | -
+//!
+//! Each item of `sensors` has the following placeholders:
+//!
+//! Placeholder   | Value                          | Type   | Unit
+//! --------------|--------------------------------|--------|--------
+//! `chip`        | The name of the chip           | Text   | -
+//! `input`       | The label of the input, or its name (e.g. `temp1`) if it has no label | Text   | -
+//! `temperature` | The temperature of the input   | Number | Degrees in `scale`
 //!
 //! Note that when block is collapsed, no placeholders are provided.
 //!
@@ -47,10 +56,19 @@
 //! chip = "*-isa-*"
 //! ```
 //!
+//! Show every input:
+//!
+//! ```toml
+//! [[block]]
+//! block = "temperature"
+//! format = " $icon {$sensors{$input: $temperature}.join(\", \")|}"
+//! ```
+//!
 //! # Icons Used
 //! - `thermometer`
 
 use super::prelude::*;
+use crate::formatting::unit::Unit;
 use sensors::FeatureType::SENSORS_FEATURE_TEMP;
 use sensors::Sensors;
 use sensors::SubfeatureType::SENSORS_SUBFEATURE_TEMP_INPUT;
@@ -60,6 +78,21 @@ const DEFAULT_IDLE: f64 = 45.0;
 const DEFAULT_INFO: f64 = 60.0;
 const DEFAULT_WARN: f64 = 80.0;
 
+/// Placeholders of each item of `sensors`
+const SENSOR_PLACEHOLDERS: &Placeholders = &[
+    ("chip", ValueType::Text),
+    ("input", ValueType::Text),
+    ("temperature", ValueType::Number),
+];
+
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("min", ValueType::Number),
+    ("average", ValueType::Number),
+    ("max", ValueType::Number),
+    ("sensors", ValueType::List(SENSOR_PLACEHOLDERS)),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct TemperatureConfig {
@@ -93,6 +126,13 @@ impl TemperatureScale {
             Self::Fahrenheit => val * 1.8 + 32.0,
         }
     }
+
+    fn unit(self) -> Unit {
+        match self {
+            Self::Celsius => Unit::Celsius,
+            Self::Fahrenheit => Unit::Fahrenheit,
+        }
+    }
 }
 
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
@@ -119,8 +159,8 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         // Perhaps it's better to just Box::leak() once and don't clone() every time?
         let chip = config.chip.clone();
         let inputs = config.inputs.clone();
-        let temp = tokio::task::spawn_blocking(move || {
-            let mut vals = Vec::new();
+        let inputs_vals = tokio::task::spawn_blocking(move || {
+            let mut inputs_vals = Vec::new();
             let sensors = Sensors::new();
             let chips = match &chip {
                 Some(chip) => sensors
@@ -129,21 +169,31 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 None => sensors.into_iter(),
             };
             for chip in chips {
+                let chip_name = chip.get_name().unwrap_or_default();
                 for feat in chip {
                     if *feat.feature_type() != SENSORS_FEATURE_TEMP {
                         continue;
                     }
-                    if let Some(inputs) = &inputs {
-                        let label = feat.get_label().error("Failed to get input label")?;
-                        if !inputs.contains(&label) {
-                            continue;
+                    let label = match &inputs {
+                        Some(inputs) => {
+                            let label = feat.get_label().error("Failed to get input label")?;
+                            if !inputs.contains(&label) {
+                                continue;
+                            }
+                            label
                         }
-                    }
+                        // Unlabeled inputs are listed by their feature name, e.g. `temp1`
+                        None => feat.get_label().unwrap_or_else(|_| feat.name().to_string()),
+                    };
                     for subfeat in feat {
                         if *subfeat.subfeature_type() == SENSORS_SUBFEATURE_TEMP_INPUT {
                             if let Ok(value) = subfeat.get_value() {
                                 if (-100.0..=150.0).contains(&value) {
-                                    vals.push(config.scale.from_celsius(value));
+                                    inputs_vals.push(SensorValue {
+                                        chip: chip_name.clone(),
+                                        input: label.clone(),
+                                        temp: config.scale.from_celsius(value),
+                                    });
                                 } else {
                                     eprintln!(
                                         "Temperature ({}) outside of range ([-100, 150])",
@@ -155,10 +205,11 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                     }
                 }
             }
-            Ok(vals)
+            Ok(inputs_vals)
         })
         .await
         .error("Failed to join tokio task")??;
+        let temp: Vec<f64> = inputs_vals.iter().map(|v| v.temp).collect();
 
         let min_temp = temp
             .iter()
@@ -186,9 +237,14 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             } else {
                 widget.set_values(map! {
                     "icon" => Value::icon(api.get_icon("thermometer")?),
-                    "average" => Value::degrees(avg_temp),
-                    "min" => Value::degrees(min_temp),
-                    "max" => Value::degrees(max_temp),
+                    "average" => Value::number_unit(avg_temp, config.scale.unit()),
+                    "min" => Value::number_unit(min_temp, config.scale.unit()),
+                    "max" => Value::number_unit(max_temp, config.scale.unit()),
+                    "sensors" => Value::list(inputs_vals.iter().map(|v| map! {
+                        "chip" => Value::text(v.chip.clone()),
+                        "input" => Value::text(v.input.clone()),
+                        "temperature" => Value::number_unit(v.temp, config.scale.unit()),
+                    }).collect()).with_instance(0),
                 });
             }
 
@@ -211,3 +267,9 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         }
     }
 }
+
+struct SensorValue {
+    chip: String,
+    input: String,
+    temp: f64,
+}
@@ -7,7 +7,7 @@
 //! `format` | Format string. See [chrono docs](https://docs.rs/chrono/0.3.0/chrono/format/strftime/index.html#specifiers) for all options. | `" $icon %a %d/%m %R "`
 //! `interval` | Update interval in seconds | `10`
 //! `timezone` | A timezone specifier (e.g. "Europe/Lisbon") | Local timezone
-//! `locale` | Locale to apply when formatting the time | System locale
+//! `locale` | Locale to apply when formatting the time. This is the `locale` option common to all blocks, so it can also be set globally. Setting it here only affects this block. | System locale
 //!
 //! Placeholder   | Value                                       | Type   | Unit
 //! --------------|---------------------------------------------|--------|-----
@@ -35,6 +35,10 @@ use chrono_tz::Tz;
 use super::prelude::*;
 use crate::formatting::config::DummyConfig;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct TimeConfig {
@@ -43,7 +47,6 @@ struct TimeConfig {
     #[default(1.into())]
     interval: Seconds,
     timezone: Option<Tz>,
-    locale: Option<String>,
 }
 
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
@@ -61,10 +64,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     // });
 
     let timezone = config.timezone;
-    let locale = match config.locale.as_deref() {
-        Some(locale) => Some(locale.try_into().ok().error("invalid locale")?),
-        None => None,
-    };
+    let locale = api.shared_config.locale.map(|l| l.chrono_locale());
 
     let mut timer = config.interval.timer();
 
@@ -2,10 +2,10 @@
 //!
 //! You can add commands to be executed to disable the toggle (`command_off`), and to enable it
 //! (`command_on`). If these command exit with a non-zero status, the block will not be toggled and
-//! the block state will be changed to give a visual warning of the failure. You also need to
-//! specify a command to determine the state of the toggle (`command_state`). When the command outputs
-//! nothing, the toggle is disabled, otherwise enabled. By specifying the interval property you can
-//! let the command_state be executed continuously.
+//! the error, including the command's stderr, is briefly shown instead of the block. You also
+//! need to specify a command to determine the state of the toggle (`command_state`). When the
+//! command outputs nothing, the toggle is disabled, otherwise enabled. By specifying the interval
+//! property you can let the command_state be executed continuously.
 //!
 //! To run those commands, the shell form `$SHELL` environment variable is used. If such variable
 //! is not presented, `sh` is used.
@@ -45,9 +45,15 @@
 //! - `toggle_on`
 
 use super::prelude::*;
+use crate::click::FLASH_DURATION;
+use crate::subprocess::{run_command, run_command_stdout};
 use std::env;
 use tokio::process::Command;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+];
+
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct ToggleConfig {
@@ -77,12 +83,12 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let shell = env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
 
     loop {
-        // Check state
-        let output = Command::new(&shell)
-            .args(["-c", &config.command_state])
-            .output()
-            .await
-            .error("Failed to run command_state")?;
+        // Check state. Only the output matters, e.g. `grep` exits with 1 when nothing matches.
+        let output = run_command(
+            Command::new(&shell).args(["-c", &config.command_state]),
+            api.command_timeout,
+        )
+        .await?;
         let is_toggled = !std::str::from_utf8(&output.stdout)
             .error("The output of command_state is invalid UTF-8")?
             .trim()
@@ -111,12 +117,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                                     } else {
                                         &config.command_on
                                     };
-                                    let output = Command::new(&shell)
-                                        .args(["-c", cmd])
-                                        .output()
-                                        .await
-                                        .error("Failed to run command")?;
-                                    if output.status.success() {
+                                    if run_toggle_command(&api, &shell, cmd).await? {
                                         widget.state = State::Idle;
                                         break;
                                     } else {
@@ -136,12 +137,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                             } else {
                                 &config.command_on
                             };
-                            let output = Command::new(&shell)
-                                .args(["-c", cmd])
-                                .output()
-                                .await
-                                .error("Failed to run command")?;
-                            if output.status.success() {
+                            if run_toggle_command(&api, &shell, cmd).await? {
                                 widget.state = State::Idle;
                                 break;
                             } else {
@@ -154,3 +150,16 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         }
     }
 }
+
+/// Run `command_on` or `command_off`. Returns `false` if it failed, in which case the error
+/// (including the command's stderr) is flashed.
+async fn run_toggle_command(api: &CommonApi, shell: &str, cmd: &str) -> Result<bool> {
+    match run_command_stdout(Command::new(shell).args(["-c", cmd]), api.command_timeout).await {
+        Ok(_) => Ok(true),
+        Err(error) => {
+            api.flash(error.to_string(), FLASH_DURATION, State::Critical)
+                .await?;
+            Ok(false)
+        }
+    }
+}
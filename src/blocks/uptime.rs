@@ -32,6 +32,11 @@
 use super::prelude::*;
 use tokio::fs::read_to_string;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("text", ValueType::Text),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct UptimeConfig {
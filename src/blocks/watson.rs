@@ -36,6 +36,10 @@ use tokio::fs::read_to_string;
 
 use super::prelude::*;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("text", ValueType::Text),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct WatsonConfig {
@@ -26,7 +26,7 @@
 //! Key | Values | Required | Default
 //! ----|--------|----------|--------
 //! `name` | `openweathermap`. | Yes | None
-//! `api_key` | Your OpenWeatherMap API key. | Yes | None
+//! `api_key` | Your OpenWeatherMap API key. Can be read from a file, a command or an environment variable, e.g. `{ file = "~/.secrets/owm" }` (see the README). | Yes | None
 //! `city_id` | OpenWeatherMap's ID for the city. | Yes* | None
 //! `place` | OpenWeatherMap 'By city name' search query. See [here](https://openweathermap.org/current) | Yes* | None
 //! `coordinates` | GPS latitude longitude coordinates as a tuple, example: `["39.2362","9.3317"]` | Yes* | None
@@ -56,8 +56,8 @@
 //! ------------------|--------------------------------------------------------------------|--------|-----
 //! `icon`            | Icon representing the weather                                      | Icon   | -
 //! `location`        | Location name (exact format depends on the service)                | Text   | -
-//! `temp`            | Temperature                                                        | Number | degrees
-//! `apparent`        | Australian Apparent Temperature                                    | Number | degrees
+//! `temp`            | Temperature                                                        | Number | Degrees Celsius or Fahrenheit, depending on `units`
+//! `apparent`        | Australian Apparent Temperature                                    | Number | Degrees Celsius or Fahrenheit, depending on `units`
 //! `humidity`        | Humidity                                                           | Number | %
 //! `weather`         | Textual brief description of the weather, e.g. "Raining"           | Text   | -
 //! `weather_verbose` | Textual verbose description of the weather, e.g. "overcast clouds" | Text   | -
@@ -93,12 +93,26 @@ use std::fmt;
 use std::sync::Arc;
 
 use super::prelude::*;
+use crate::formatting::unit::Unit;
 
 mod met_no;
 mod open_weather_map;
 
 const IP_API_URL: &str = "https://ipapi.co/json";
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("location", ValueType::Text),
+    ("temp", ValueType::Number),
+    ("apparent", ValueType::Number),
+    ("humidity", ValueType::Number),
+    ("weather", ValueType::Text),
+    ("weather_verbose", ValueType::Text),
+    ("wind", ValueType::Number),
+    ("wind_kmh", ValueType::Number),
+    ("direction", ValueType::Text),
+];
+
 #[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
 struct WeatherConfig {
@@ -155,6 +169,7 @@ struct WeatherResult {
     location: String,
     temp: f64,
     apparent: f64,
+    temperature_unit: Unit,
     humidity: f64,
     weather: String,
     weather_verbose: String,
@@ -169,8 +184,8 @@ impl WeatherResult {
         Ok(map! {
             "icon" => Value::icon(api.get_icon(self.icon.to_icon_str())?),
             "location" => Value::text(self.location),
-            "temp" => Value::degrees(self.temp),
-            "apparent" => Value::degrees(self.apparent),
+            "temp" => Value::number_unit(self.temp, self.temperature_unit),
+            "apparent" => Value::number_unit(self.apparent, self.temperature_unit),
             "humidity" => Value::percents(self.humidity),
             "weather" => Value::text(self.weather),
             "weather_verbose" => Value::text(self.weather_verbose),
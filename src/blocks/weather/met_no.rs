@@ -176,6 +176,7 @@ impl WeatherProvider for Service {
             location: "Unknown".to_string(),
             temp,
             apparent: australian_apparent_temp(temp, humidity, wind_speed),
+            temperature_unit: Unit::Celsius,
             humidity,
             weather: translated.clone(),
             weather_verbose: translated,
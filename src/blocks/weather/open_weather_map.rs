@@ -9,7 +9,7 @@ pub(super) const PLACE_ENV: &str = "OPENWEATHERMAP_PLACE";
 #[serde(tag = "name", rename_all = "lowercase")]
 pub(super) struct Config {
     #[serde(default = "getenv_openweathermap_api_key")]
-    api_key: Option<String>,
+    api_key: Secret,
     #[serde(default = "getenv_openweathermap_city_id")]
     city_id: Option<String>,
     #[serde(default = "getenv_openweathermap_place")]
@@ -31,8 +31,8 @@ impl Service {
     }
 }
 
-fn getenv_openweathermap_api_key() -> Option<String> {
-    std::env::var(API_KEY_ENV).ok()
+fn getenv_openweathermap_api_key() -> Secret {
+    Secret::env(API_KEY_ENV)
 }
 fn getenv_openweathermap_city_id() -> Option<String> {
     std::env::var(CITY_ID_ENV).ok()
@@ -74,9 +74,7 @@ struct ApiWeather {
 #[async_trait]
 impl WeatherProvider for Service {
     async fn get_weather(&self, autolocated: Option<Coordinates>) -> Result<WeatherResult> {
-        let api_key = self.config.api_key.as_ref().or_error(|| {
-            format!("missing key 'service.api_key' and environment variable {API_KEY_ENV}",)
-        })?;
+        let api_key = self.config.api_key.get().await?;
 
         let location_query = autolocated
             .map(|al| format!("lat={}&lon={}", al.latitude, al.longitude))
@@ -100,19 +98,26 @@ impl WeatherProvider for Service {
             lang = self.config.lang,
         );
 
+        // The URL contains the API key, so it is removed from errors
         let data: ApiResponse = REQWEST_CLIENT
             .get(url)
             .send()
             .await
+            .map_err(reqwest::Error::without_url)
             .error("Forecast request failed")?
             .json()
             .await
+            .map_err(reqwest::Error::without_url)
             .error("Forecast request failed")?;
 
         Ok(WeatherResult {
             location: data.name,
             temp: data.main.temp,
             apparent: data.main.feels_like,
+            temperature_unit: match self.config.units {
+                UnitSystem::Metric => Unit::Celsius,
+                UnitSystem::Imperial => Unit::Fahrenheit,
+            },
             humidity: data.main.humidity,
             weather: data.weather[0].main.clone(),
             weather_verbose: data.weather[0].description.clone(),
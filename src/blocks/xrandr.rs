@@ -1,6 +1,6 @@
 //! X11 screen information
 //!
-//! X11 screen information (name, brightness, resolution). With a click you can toggle through your active screens and with wheel up and down you can adjust the selected screens brightness. Regarding brightness control, xrandr changes the brightness of the display using gamma rather than changing the brightness in hardware, so if that is not desirable then consider using the `backlight` block instead.
+//! X11 screen information (name, brightness, resolution). With a click you can toggle through your active screens and with wheel up and down you can adjust the selected screens brightness. If `$brightness` is displayed as a bar (e.g. `$brightness.bar(10)`), clicking on the bar sets the brightness. Regarding brightness control, xrandr changes the brightness of the display using gamma rather than changing the brightness in hardware, so if that is not desirable then consider using the `backlight` block instead.
 //!
 //! NOTE: Some users report issues (e.g. [here](https://github.com/greshake/i3status-rust/issues/274) and [here](https://github.com/greshake/i3status-rust/issues/668) when using this block. The cause is currently unknown, however setting a higher update interval may help.
 //!
@@ -39,6 +39,15 @@ use crate::subprocess::spawn_shell;
 use regex::RegexSet;
 use tokio::process::Command;
 
+pub const PLACEHOLDERS: &Placeholders = &[
+    ("icon", ValueType::Icon),
+    ("display", ValueType::Text),
+    ("brightness", ValueType::Number),
+    ("brightness_icon", ValueType::Icon),
+    ("resolution", ValueType::Text),
+    ("res_icon", ValueType::Icon),
+];
+
 #[derive(Deserialize, Debug, SmartDefault)]
 #[serde(deny_unknown_fields, default)]
 struct XrandrConfig {
@@ -70,7 +79,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             widget.set_values(if let Some(mon) = monitors.get(cur_indx) {
                 map! {
                     "display" => Value::text(mon.name.clone()),
-                    "brightness" => Value::percents(mon.brightness),
+                    "brightness" => Value::percents(mon.brightness).with_slider(),
                     //TODO: change `brightness_icon` based on `brightness`
                     "brightness_icon" => Value::icon(api.get_icon("backlight_full")?),
                     "resolution" => Value::text(mon.resolution.clone()),
@@ -87,6 +96,12 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 event = api.event() => match event {
                     UpdateRequest => break,
                     Click(click) => {
+                        if let Some(bright) = click.slider_value() {
+                            if let Some(monitor) = monitors.get_mut(cur_indx) {
+                                monitor.set_brightness(bright.round() as u32);
+                            }
+                            continue;
+                        }
                         match click.button {
                             MouseButton::Left => {
                                 cur_indx += 1;
@@ -1,10 +1,16 @@
 use std::fmt;
+use std::time::Duration;
 
 use serde::de::{self, Deserializer, Visitor};
 use serde::Deserialize;
+use tokio::sync::mpsc;
 
 use crate::errors::{Result, ResultExt};
-use crate::subprocess::{spawn_shell, spawn_shell_sync};
+use crate::formatting::{config::Config as FormatConfig, Format};
+use crate::protocol::i3bar_event::I3BarEvent;
+use crate::subprocess::{run_command, spawn_shell_sync_with_env, spawn_shell_with_env};
+use crate::widget::{State, Widget};
+use crate::{Request, RequestCmd};
 
 /// Can be one of `left`, `middle`, `right`, `wheel_up`, `wheel_down`, `forward`, `back`, or
 /// `double_left`.
@@ -25,38 +31,184 @@ pub enum MouseButton {
     DoubleLeft,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+impl MouseButton {
+    /// The number of the button in the i3bar protocol (`0` if unknown)
+    pub fn number(self) -> u8 {
+        use MouseButton::*;
+        match self {
+            Left | DoubleLeft => 1,
+            Middle => 2,
+            Right => 3,
+            WheelUp => 4,
+            WheelDown => 5,
+            Back => 8,
+            Forward => 9,
+            Unknown => 0,
+        }
+    }
+}
+
+/// How long the output of a command with `flash_output` is shown
+pub const FLASH_DURATION: Duration = Duration::from_secs(3);
+
+/// How long the prompt of an entry with `confirm` waits for the second click
+pub const CONFIRM_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Lock modifiers (Num Lock and Caps Lock) which are ignored when matching clicks to entries
+const IGNORED_MODIFIERS: &[&str] = &["Mod2", "Lock"];
+
+/// The instance reported by clicks on values marked with `Value::with_slider`
+pub const SLIDER_INSTANCE: usize = usize::MAX;
+
+/// What to do after a click has been handled. Everything except `pass` is done by the bar itself,
+/// so these actions work the same way for every block.
+#[derive(Debug, Clone)]
 pub struct PostActions {
     pub pass: bool,
     pub update: bool,
+    pub hide: bool,
+    pub expand: bool,
+    pub toggle_format_alt: bool,
+    pub cycle_format: Option<Vec<Format>>,
+    pub copy_to_clipboard: Option<Format>,
+}
+
+/// Where the output of commands with `flash_output` is sent once they exit
+#[derive(Clone)]
+pub struct FlashTarget {
+    pub block_id: usize,
+    pub request_sender: mpsc::Sender<Request>,
+    /// How long the command may run, see `command_timeout`
+    pub timeout: Duration,
+}
+
+impl FlashTarget {
+    /// Briefly show `text` instead of the block
+    pub async fn flash(&self, text: String, state: State) {
+        let request = Request {
+            block_id: self.block_id,
+            cmd: RequestCmd::Flash {
+                text,
+                duration: FLASH_DURATION,
+                state,
+            },
+        };
+        let _ = self.request_sender.send(request).await;
+    }
+}
+
+impl Default for PostActions {
+    fn default() -> Self {
+        Self {
+            pass: true,
+            update: false,
+            hide: false,
+            expand: false,
+            toggle_format_alt: false,
+            cycle_format: None,
+            copy_to_clipboard: None,
+        }
+    }
 }
 
 #[derive(Deserialize, Debug, Clone, Default)]
 pub struct ClickHandler(Vec<ClickConfigEntry>);
 
 impl ClickHandler {
-    pub async fn handle(&self, button: MouseButton) -> Result<PostActions> {
-        Ok(match self.0.iter().find(|e| e.button == button) {
+    /// The formats used by `cycle_format` and `copy_to_clipboard`
+    pub fn formats(&self) -> impl Iterator<Item = &Format> {
+        self.0.iter().flat_map(|e| {
+            e.cycle_format
+                .iter()
+                .flatten()
+                .chain(e.copy_to_clipboard.as_ref())
+        })
+    }
+
+    /// Toggle `format_alt` on left click, unless the left button already has an action
+    pub fn toggle_format_alt_on_left_click(&mut self) {
+        if !self
+            .0
+            .iter()
+            .any(|e| e.button == MouseButton::Left && e.modifiers.is_none())
+        {
+            self.0.push(ClickConfigEntry {
+                button: MouseButton::Left,
+                modifiers: None,
+                cmd: None,
+                sync: false,
+                update: false,
+                pass: true,
+                hide: false,
+                expand: false,
+                toggle_format_alt: true,
+                cycle_format: None,
+                copy_to_clipboard: None,
+                flash_output: false,
+                confirm: None,
+            });
+        }
+    }
+
+    /// Find the entry for this click. Entries which list the exact set of held modifiers take
+    /// precedence over entries which do not mention modifiers at all. Num Lock and Caps Lock
+    /// (`IGNORED_MODIFIERS`) are not taken into account.
+    fn find(&self, event: &I3BarEvent) -> Option<&ClickConfigEntry> {
+        let held = relevant_modifiers(&event.modifiers);
+        let same_modifiers = |modifiers: &[String]| {
+            let modifiers = relevant_modifiers(modifiers);
+            modifiers.len() == held.len() && modifiers.iter().all(|m| held.contains(m))
+        };
+        let mut entries = self.0.iter().filter(|e| e.button == event.button);
+        entries
+            .clone()
+            .find(|e| e.modifiers.as_deref().is_some_and(same_modifiers))
+            .or_else(|| entries.find(|e| e.modifiers.is_none()))
+    }
+
+    /// The confirmation prompt of the entry for this click, if it has one
+    pub fn confirm_msg(&self, event: &I3BarEvent) -> Option<&str> {
+        self.find(event)?.confirm.as_deref()
+    }
+
+    /// Handle a click on the block `block_name`. Placeholders in `cmd` are replaced with the
+    /// values of `widget`. Commands with `flash_output` run in the background, and their output
+    /// is sent to `flash_target` when they exit.
+    pub async fn handle(
+        &self,
+        event: &I3BarEvent,
+        block_name: &str,
+        widget: Option<&Widget>,
+        flash_target: &FlashTarget,
+    ) -> Result<PostActions> {
+        let button = event.button;
+        Ok(match self.find(event) {
             Some(entry) => {
                 if let Some(cmd) = &entry.cmd {
-                    if entry.sync {
-                        spawn_shell_sync(cmd).await
+                    let cmd = substitute_placeholders(cmd, |name| widget?.value_text(name));
+                    let env = click_env(event, block_name);
+                    let error = || format!("'{:?}' button handler: Failed to run '{}", button, cmd);
+                    if entry.flash_output {
+                        spawn_flash_output(&cmd, &env, flash_target);
+                    } else if entry.sync {
+                        spawn_shell_sync_with_env(&cmd, &env)
+                            .await
+                            .or_error(error)?;
                     } else {
-                        spawn_shell(cmd)
+                        spawn_shell_with_env(&cmd, &env).or_error(error)?;
                     }
-                    .or_error(|| {
-                        format!("'{:?}' button handler: Failed to run '{}", button, cmd)
-                    })?;
                 }
                 PostActions {
                     pass: entry.pass,
                     update: entry.update,
+                    hide: entry.hide,
+                    expand: entry.expand,
+                    toggle_format_alt: entry.toggle_format_alt,
+                    cycle_format: entry.cycle_format.clone(),
+                    copy_to_clipboard: entry.copy_to_clipboard.clone(),
                 }
             }
-            None => PostActions {
-                pass: true,
-                update: false,
-            },
+            None => PostActions::default(),
         })
     }
 }
@@ -66,6 +218,10 @@ impl ClickHandler {
 pub struct ClickConfigEntry {
     /// Which button to handle
     button: MouseButton,
+    /// Which modifier keys have to be held, e.g. `["Shift"]`. If not set, the entry matches
+    /// regardless of modifiers unless there is an entry for the exact modifiers.
+    #[serde(default)]
+    modifiers: Option<Vec<String>>,
     /// Which command to run
     #[serde(default)]
     cmd: Option<String>,
@@ -78,12 +234,147 @@ pub struct ClickConfigEntry {
     /// Whether to also pass click event to the block (if block has an action for the button and `cmd` is also defined, both will be run if `pass` is true) (default is `true`)
     #[serde(default = "return_true")]
     pass: bool,
+    /// Hide the block until its content changes
+    #[serde(default)]
+    hide: bool,
+    /// Show only this block, until the next click with `expand` (default is `false`)
+    #[serde(default)]
+    expand: bool,
+    /// Switch between the block's format and `format_alt` (default is `false`)
+    #[serde(default)]
+    toggle_format_alt: bool,
+    /// Formats to switch to on each click, after the last one the block's format is used again
+    #[serde(default, deserialize_with = "deserialize_formats")]
+    cycle_format: Option<Vec<Format>>,
+    /// Copy the block's values, rendered with this format, to the clipboard
+    #[serde(default, deserialize_with = "deserialize_format")]
+    copy_to_clipboard: Option<Format>,
+    /// Wait for `cmd` to exit and briefly show the first line of its output (default is `false`)
+    #[serde(default)]
+    flash_output: bool,
+    /// Show this prompt and only handle the click if the same button is clicked again
+    #[serde(default)]
+    confirm: Option<String>,
+}
+
+fn relevant_modifiers(modifiers: &[String]) -> Vec<&str> {
+    modifiers
+        .iter()
+        .map(String::as_str)
+        .filter(|m| !IGNORED_MODIFIERS.contains(m))
+        .collect()
+}
+
+/// Run `cmd` off the main loop, killing it after the timeout, and flash its output
+fn spawn_flash_output(cmd: &str, env: &[(&str, String)], target: &FlashTarget) {
+    let mut command = tokio::process::Command::new("sh");
+    command.args(["-c", cmd]).envs(env.iter().cloned());
+    let target = target.clone();
+    tokio::spawn(async move {
+        let message = match run_command(&mut command, target.timeout).await {
+            Ok(output) => flash_message(&output),
+            Err(error) => Some((error.to_string(), State::Critical)),
+        };
+        if let Some((text, state)) = message {
+            target.flash(text, state).await;
+        }
+    });
+}
+
+/// The first line of the command's stdout (or stderr if it failed without output). Failed
+/// commands are shown in the critical state.
+fn flash_message(output: &std::process::Output) -> Option<(String, State)> {
+    let first_line = |bytes: &[u8]| {
+        String::from_utf8_lossy(bytes)
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map(str::to_string)
+    };
+    if output.status.success() {
+        first_line(&output.stdout).map(|line| (line, State::Idle))
+    } else {
+        first_line(&output.stdout)
+            .or_else(|| first_line(&output.stderr))
+            .map(|line| (line, State::Critical))
+    }
+}
+
+/// Replace `$name` in a shell command with the text of the placeholder `name`, in single quotes.
+/// The quotes end any quoted string around `$name`, so placeholders must not be used inside
+/// quotes. Names which are not placeholders (e.g. `$HOME`) are left for the shell to expand.
+fn substitute_placeholders(cmd: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut retval = String::with_capacity(cmd.len());
+    let mut rest = cmd;
+    while let Some(pos) = rest.find('$') {
+        retval.push_str(&rest[..pos]);
+        rest = &rest[pos + 1..];
+        let name_len = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+        let name = &rest[..name_len];
+        match value(name).filter(|_| !name.is_empty()) {
+            Some(text) => {
+                retval.push('\'');
+                retval.push_str(&text.replace('\'', r"'\''"));
+                retval.push('\'');
+            }
+            None => {
+                retval.push('$');
+                retval.push_str(name);
+            }
+        }
+        rest = &rest[name_len..];
+    }
+    retval.push_str(rest);
+    retval
+}
+
+/// Environment variables describing a click, named like in i3blocks
+fn click_env(event: &I3BarEvent, block_name: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("BLOCK_NAME", block_name.to_string()),
+        (
+            "BLOCK_INSTANCE",
+            event.instance.map(|i| i.to_string()).unwrap_or_default(),
+        ),
+        ("BLOCK_BUTTON", event.button.number().to_string()),
+        ("BLOCK_MODIFIERS", event.modifiers.join(",")),
+        ("BLOCK_X", (event.x as i64).to_string()),
+        ("BLOCK_Y", (event.y as i64).to_string()),
+        ("BLOCK_RELATIVE_X", (event.relative_x as i64).to_string()),
+        ("BLOCK_RELATIVE_Y", (event.relative_y as i64).to_string()),
+        ("BLOCK_WIDTH", (event.width as i64).to_string()),
+        ("BLOCK_HEIGHT", (event.height as i64).to_string()),
+    ]
 }
 
 fn return_true() -> bool {
     true
 }
 
+fn deserialize_format<'de, D>(deserializer: D) -> Result<Option<Format>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    FormatConfig::deserialize(deserializer)?
+        .with_default("")
+        .map(Some)
+        .map_err(de::Error::custom)
+}
+
+fn deserialize_formats<'de, D>(deserializer: D) -> Result<Option<Vec<Format>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<FormatConfig>::deserialize(deserializer)?
+        .into_iter()
+        .map(|format| format.with_default(""))
+        .collect::<Result<_>>()
+        .map(Some)
+        .map_err(de::Error::custom)
+}
+
 impl<'de> Deserialize<'de> for MouseButton {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -150,3 +441,82 @@ impl<'de> Deserialize<'de> for MouseButton {
         deserializer.deserialize_any(MouseButtonVisitor)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_in_cmd() {
+        let value = |name: &str| match name {
+            "ssid" => Some("My 'Wifi'".to_string()),
+            "ip" => Some("10.0.0.2".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            substitute_placeholders("nm-connection-editor --edit $ssid", value),
+            r"nm-connection-editor --edit 'My '\''Wifi'\'''"
+        );
+        assert_eq!(
+            substitute_placeholders("echo $ip:$HOME $ ${ip}", value),
+            "echo '10.0.0.2':$HOME $ ${ip}"
+        );
+    }
+
+    #[test]
+    fn lock_modifiers_are_ignored() {
+        let config: std::collections::HashMap<String, ClickHandler> = toml::from_str(
+            r#"
+            [[click]]
+            button = "left"
+            modifiers = ["Shift"]
+            cmd = "shift"
+            [[click]]
+            button = "left"
+            cmd = "plain"
+            "#,
+        )
+        .unwrap();
+        let handler = &config["click"];
+        let cmd = |modifiers: &[&str]| {
+            let event = I3BarEvent {
+                id: 0,
+                instance: None,
+                button: MouseButton::Left,
+                modifiers: modifiers.iter().map(|m| m.to_string()).collect(),
+                x: 0.,
+                y: 0.,
+                relative_x: 0.,
+                relative_y: 0.,
+                width: 0.,
+                height: 0.,
+                slider: None,
+            };
+            handler.find(&event).and_then(|e| e.cmd.clone())
+        };
+        assert_eq!(cmd(&["Shift"]).as_deref(), Some("shift"));
+        assert_eq!(cmd(&["Shift", "Mod2"]).as_deref(), Some("shift"));
+        assert_eq!(cmd(&["Lock", "Shift", "Mod2"]).as_deref(), Some("shift"));
+        assert_eq!(cmd(&["Mod2"]).as_deref(), Some("plain"));
+        assert_eq!(cmd(&["Shift", "Control"]).as_deref(), Some("plain"));
+    }
+
+    #[test]
+    fn flash_messages() {
+        use std::os::unix::process::ExitStatusExt;
+        let output = |code, stdout: &str, stderr: &str| std::process::Output {
+            status: std::process::ExitStatus::from_raw(code << 8),
+            stdout: stdout.into(),
+            stderr: stderr.into(),
+        };
+        assert_eq!(
+            flash_message(&output(0, "\nfirst\nsecond\n", "")),
+            Some(("first".into(), State::Idle))
+        );
+        assert_eq!(
+            flash_message(&output(1, "", "oops\n")),
+            Some(("oops".into(), State::Critical))
+        );
+        assert_eq!(flash_message(&output(0, "", "ignored")), None);
+    }
+}
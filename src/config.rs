@@ -1,19 +1,26 @@
+pub mod templates;
+pub mod vars;
+
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::sync::Arc;
 use toml::value;
 
+use crate::formatting::locale::Locale;
 use crate::icons::Icons;
-use crate::themes::Theme;
+use crate::themes::ThemeSet;
 use crate::util::default;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct SharedConfig {
     #[serde(default)]
-    pub theme: Arc<Theme>,
+    pub theme: Arc<ThemeSet>,
     #[serde(default)]
     pub icons: Arc<Icons>,
     #[serde(default = "Config::default_icons_format")]
     pub icons_format: Arc<String>,
+    #[serde(default)]
+    pub locale: Option<Locale>,
 }
 
 impl SharedConfig {
@@ -21,9 +28,17 @@ impl SharedConfig {
         if icon.is_empty() {
             Some(String::new())
         } else {
-            Some(self.icons_format.replace("{icon}", self.icons.0.get(icon)?))
+            Some(self.icons_format.replace("{icon}", self.icons.get(icon)?))
         }
     }
+
+    /// Pick an icon of a family by `fraction` (see `Icons::get_in_family`)
+    pub fn get_icon_in_family(&self, family: &str, fraction: f64) -> Option<String> {
+        Some(
+            self.icons_format
+                .replace("{icon}", self.icons.get_in_family(family, fraction)?),
+        )
+    }
 }
 
 impl Default for SharedConfig {
@@ -32,6 +47,7 @@ impl Default for SharedConfig {
             theme: default(),
             icons: default(),
             icons_format: Arc::new("{icon}".into()),
+            locale: None,
         }
     }
 }
@@ -49,11 +65,19 @@ pub struct Config {
     #[serde(default)]
     pub double_click_delay: u64,
 
+    /// The real-time signal (`SIGRTMIN+n`) which switches between the light and dark themes
+    #[serde(default)]
+    pub dark_mode_signal: Option<i32>,
+
     #[serde(default = "Config::default_error_format")]
     pub error_format: String,
     #[serde(default = "Config::default_error_fullscreen_format")]
     pub error_fullscreen_format: String,
 
+    /// Tables which blocks can include with `template = "<name>"`
+    #[serde(default)]
+    pub templates: HashMap<String, value::Table>,
+
     #[serde(rename = "block")]
     pub blocks: Vec<value::Value>,
 }
@@ -0,0 +1,125 @@
+//! Block templates, defined in `[templates.<name>]` and used with `template = "<name>"`
+
+use std::collections::HashMap;
+
+use toml::value::{Table, Value};
+
+use crate::errors::*;
+
+/// How deeply templates may use other templates
+const MAX_DEPTH: usize = 16;
+
+/// Merge the templates named in the block's `template` key under the block's own keys. With a
+/// list of templates, later templates take precedence over earlier ones.
+pub fn apply(block: &mut Value, templates: &HashMap<String, Table>) -> Result<()> {
+    if let Some(block) = block.as_table_mut() {
+        apply_table(block, templates, 0)?;
+    }
+    Ok(())
+}
+
+fn apply_table(table: &mut Table, templates: &HashMap<String, Table>, depth: usize) -> Result<()> {
+    let names = match table.remove("template") {
+        None => return Ok(()),
+        Some(Value::String(name)) => vec![name],
+        Some(Value::Array(names)) => names
+            .into_iter()
+            .map(|name| match name {
+                Value::String(name) => Ok(name),
+                _ => Err(Error::new("'template' must be a name or a list of names")),
+            })
+            .collect::<Result<_>>()?,
+        Some(_) => return Err(Error::new("'template' must be a name or a list of names")),
+    };
+    if depth >= MAX_DEPTH {
+        return Err(Error::new(
+            "Templates are nested too deeply (do templates use each other?)",
+        ));
+    }
+    for name in names.iter().rev() {
+        let mut template = templates
+            .get(name)
+            .or_error(|| format!("Template '{name}' not found"))?
+            .clone();
+        apply_table(&mut template, templates, depth + 1)?;
+        merge(table, template);
+    }
+    Ok(())
+}
+
+/// Add the keys of `template` which are not in `table`. Tables are merged recursively and arrays
+/// of tables (e.g. `[[block.click]]`) are concatenated, with the entries of `table` first.
+fn merge(table: &mut Table, template: Table) {
+    for (key, template_value) in template {
+        match (table.get_mut(&key), template_value) {
+            (None, template_value) => {
+                table.insert(key, template_value);
+            }
+            (Some(Value::Table(value)), Value::Table(template_value)) => {
+                merge(value, template_value);
+            }
+            (Some(Value::Array(value)), Value::Array(template_value))
+                if value.iter().chain(&template_value).all(Value::is_table) =>
+            {
+                value.extend(template_value);
+            }
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates() {
+        let config: Table = toml::from_str(
+            r##"
+            [templates.net]
+            block = "net"
+            format = " $icon $speed_down "
+            theme_overrides = { idle_bg = "#000000", idle_fg = "#ffffff" }
+            [[templates.net.click]]
+            button = "left"
+            cmd = "nm-connection-editor"
+
+            [templates.wifi]
+            template = "net"
+            format = " $icon $ssid "
+
+            [block]
+            template = ["wifi"]
+            device = "wlan0"
+            theme_overrides = { idle_bg = "#111111" }
+            [[block.click]]
+            button = "right"
+            cmd = "iwgtk"
+            "##,
+        )
+        .unwrap();
+        let templates: HashMap<String, Table> = config["templates"].clone().try_into().unwrap();
+        let mut block = config["block"].clone();
+        apply(&mut block, &templates).unwrap();
+
+        assert!(block.get("template").is_none());
+        assert_eq!(block["block"].as_str(), Some("net"));
+        assert_eq!(block["format"].as_str(), Some(" $icon $ssid "));
+        assert_eq!(block["device"].as_str(), Some("wlan0"));
+        assert_eq!(
+            block["theme_overrides"]["idle_bg"].as_str(),
+            Some("#111111")
+        );
+        assert_eq!(
+            block["theme_overrides"]["idle_fg"].as_str(),
+            Some("#ffffff")
+        );
+        let click = block["click"].as_array().unwrap();
+        assert_eq!(click.len(), 2);
+        assert_eq!(click[0]["cmd"].as_str(), Some("iwgtk"));
+        assert_eq!(click[1]["cmd"].as_str(), Some("nm-connection-editor"));
+
+        let mut block: Value = toml::from_str("template = \"missing\"").unwrap();
+        assert!(apply(&mut block, &templates).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+//! Variables defined in `[vars]` and `${...}` interpolation of the configuration
+
+use std::time::Duration;
+
+use toml::value::{Table, Value};
+
+use crate::errors::*;
+use crate::subprocess::run_command_stdout_blocking;
+
+/// How deeply variables may refer to each other
+const MAX_DEPTH: usize = 16;
+
+/// How long `${cmd:...}` may run before startup fails
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Remove the `[vars]` table from the configuration and interpolate all strings in the rest of it:
+///
+/// - `${name}` is replaced with the variable `name`. If the whole string is `${name}`, the value
+///   keeps its type, so that numbers, lists and tables can be reused too.
+/// - `${env:NAME}` is replaced with the environment variable `NAME`.
+/// - `${file:path}` is replaced with the contents of the file, without the trailing newline.
+/// - `${cmd:command}` is replaced with the output of the shell command.
+/// - `$${` is replaced with `${`.
+///
+/// `${...}` which does not name a variable (e.g. `${HOME}` in a shell command) is left as is, and so
+/// are shell expansions like `${cmd:-default}`.
+pub fn interpolate(config: &mut Value) -> Result<()> {
+    let vars = match config
+        .as_table_mut()
+        .and_then(|config| config.remove("vars"))
+    {
+        Some(Value::Table(vars)) => vars,
+        Some(_) => return Err(Error::new("'vars' must be a table")),
+        None => Table::new(),
+    };
+    Vars(vars).interpolate_value(config, 0)
+}
+
+struct Vars(Table);
+
+impl Vars {
+    fn interpolate_value(&self, value: &mut Value, depth: usize) -> Result<()> {
+        match value {
+            Value::String(string) => *value = self.interpolate_str(string, depth)?,
+            Value::Array(array) => {
+                for value in array {
+                    self.interpolate_value(value, depth)?;
+                }
+            }
+            Value::Table(table) => {
+                for (_, value) in table.iter_mut() {
+                    self.interpolate_value(value, depth)?;
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn interpolate_str(&self, string: &str, depth: usize) -> Result<Value> {
+        if let Some(name) = string
+            .strip_prefix("${")
+            .and_then(|name| name.strip_suffix('}'))
+        {
+            if self.0.contains_key(name) {
+                return self.var(name, depth);
+            }
+        }
+
+        let mut result = String::new();
+        let mut rest = string;
+        while let Some(start) = rest.find("${") {
+            let (text, expr) = (&rest[..start], &rest[start + 2..]);
+            if let Some(text) = text.strip_suffix('$') {
+                result.push_str(text);
+                result.push_str("${");
+                rest = expr;
+                continue;
+            }
+            result.push_str(text);
+            let len = closing_brace(expr).or_error(|| format!("Unclosed '${{' in '{string}'"))?;
+            match self.expand(&expr[..len], depth)? {
+                Some(value) => result.push_str(&value),
+                None => result.push_str(&rest[start..start + len + 3]),
+            }
+            rest = &expr[len + 1..];
+        }
+        result.push_str(rest);
+        Ok(Value::String(result))
+    }
+
+    /// The text of `${expr}`, or `None` if it is not meant for us
+    fn expand(&self, expr: &str, depth: usize) -> Result<Option<String>> {
+        // `${name:-word}`, `${name:=word}`, `${name:?word}` and `${name:+word}` are for the shell
+        if let Some((_, rest)) = expr.split_once(':') {
+            if rest.starts_with(['-', '=', '?', '+']) {
+                return Ok(None);
+            }
+        }
+        if let Some(name) = expr.strip_prefix("env:") {
+            std::env::var(name)
+                .or_error(|| format!("Environment variable '{name}' is not set"))
+                .map(Some)
+        } else if let Some(path) = expr.strip_prefix("file:") {
+            let path = shellexpand::tilde(path.trim());
+            let contents = std::fs::read_to_string(path.as_ref())
+                .or_error(|| format!("Failed to read '{path}'"))?;
+            Ok(Some(contents.trim_end_matches('\n').to_string()))
+        } else if let Some(cmd) = expr.strip_prefix("cmd:") {
+            let mut command = tokio::process::Command::new("sh");
+            command.args(["-c", cmd]);
+            let stdout = run_command_stdout_blocking(command, COMMAND_TIMEOUT)?;
+            Ok(Some(stdout.trim_end_matches('\n').to_string()))
+        } else if self.0.contains_key(expr) {
+            match self.var(expr, depth)? {
+                Value::String(value) => Ok(Some(value)),
+                Value::Integer(value) => Ok(Some(value.to_string())),
+                Value::Float(value) => Ok(Some(value.to_string())),
+                Value::Boolean(value) => Ok(Some(value.to_string())),
+                _ => Err(Error::new(format!(
+                    "Variable '{expr}' is not a string or a number"
+                ))),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn var(&self, name: &str, depth: usize) -> Result<Value> {
+        if depth >= MAX_DEPTH {
+            return Err(Error::new(format!(
+                "Variable '{name}' is nested too deeply (do variables refer to each other?)"
+            )));
+        }
+        let mut value = self.0[name].clone();
+        self.interpolate_value(&mut value, depth + 1)?;
+        Ok(value)
+    }
+}
+
+/// The position of the `}` which closes an already opened `{`
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut open = 1;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => open += 1,
+            '}' => {
+                open -= 1;
+                if open == 0 {
+                    return Some(i);
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolation() {
+        std::env::set_var("I3RS_TEST_VAR", "from env");
+        let mut config: Value = toml::from_str(
+            r##"
+            [vars]
+            accent = "#ff0000"
+            warning = 80
+            greeting = "hello ${env:I3RS_TEST_VAR}"
+
+            [[block]]
+            format = " ${greeting}! ${accent} "
+            warning = "${warning}"
+            command = "echo ${HOME} $${accent} ${cmd:printf '{}'} ${cmd:-default}"
+            "##,
+        )
+        .unwrap();
+        interpolate(&mut config).unwrap();
+        assert!(config.get("vars").is_none());
+        let block = &config["block"][0];
+        assert_eq!(block["format"].as_str(), Some(" hello from env! #ff0000 "));
+        assert_eq!(block["warning"].as_integer(), Some(80));
+        assert_eq!(
+            block["command"].as_str(),
+            Some("echo ${HOME} ${accent} {} ${cmd:-default}")
+        );
+
+        let mut config: Value = toml::from_str("x = \"${cmd:echo oops >&2; exit 1}\"").unwrap();
+        let error = interpolate(&mut config).unwrap_err();
+        assert!(error.to_string().contains("oops"));
+
+        let mut config: Value = toml::from_str("[vars]\na = \"${a}\"\n[x]\nb = \"${a}\"").unwrap();
+        assert!(interpolate(&mut config).is_err());
+    }
+}
@@ -32,6 +32,14 @@ impl<I: Iterator<Item = char>> CollectEscaped for I {
     }
 }
 
+/// Undo the escaping of `collect_pango`
+pub fn unescape_pango(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -41,5 +49,7 @@ mod tests {
         let orig = "&my 'text' <>";
         let escaped: String = orig.chars().collect_pango();
         assert_eq!(escaped, "&amp;my &#39;text&#39; &lt;&gt;");
+        assert_eq!(unescape_pango(&escaped), orig);
+        assert_eq!(unescape_pango("&amp;lt;"), "&lt;");
     }
 }
@@ -19,6 +19,7 @@
 //! Text                      | `str`
 //! Number                    | `eng`
 //! [Flag](#how-to-use-flags) | N/A
+//! [List](#how-to-use-lists) | N/A
 //!
 //! # Formatters
 //!
@@ -28,17 +29,23 @@
 //!
 //! ## `str` - Format text
 //!
+//! Widths are measured in columns, so wide characters (e.g. CJK) count as two.
+//!
 //! Argument | Default value
 //! ---------|--------------
 //! Min width - if text is shorter it will be padded using spaces | `0`
 //! Max width - if text is longer it will be truncated | `inf`
+//! Align - where to place the text if it is padded: `left`, `center` or `right` | `left`
+//! Ellipsis - appended to the text if it was truncated, e.g. `…` | `""`
+//!
+//! For example, `$title.str(10, 10, center, "...")`.
 //!
 //! ## `rot-str` - Rotating text
 //!
 //! Argument | Default value
 //! ---------|--------------
 //! Width - if text is shorter it will be padded using spaces | `15`
-//! Interval - If text is longer than `width` it will be rotated every `interval` seconds | `1.0`
+//! Interval - If text is longer than `width` it will be rotated every `interval` seconds | `0.5`
 //!
 //! ## `eng` - Format numbers using engineering notation
 //!
@@ -48,12 +55,96 @@
 //! Unit - some values have a [unit](unit::Unit), and it is possible to convert them by setting this option. Prepend this with a space to split unit from number/prefix. Prepend this with a `_` to hide. | `auto`
 //! Prefix - specifiy this argument if you want to set the minimal [SI prefix](prefix::Prefix). Prepend this with a space to split prefix from number. Prepend this with a `_` to hide. Prepend this with a `!` to force the prefix. | `auto`
 //!
+//! Units of the same quantity can be converted to each other: bytes and bits (`B`, `b`), rates
+//! (`B/s`, `b/s`), temperatures (`C`, `F`, `K`) and time (`s`, `min`, `h`, `d`). For example,
+//! `$max.eng(2,F)` shows a temperature in Fahrenheit and `$speed_down.eng(3,b/s)` shows a network
+//! speed in bits per second. For compatibility, `B` and `b` can also be used for rates, and `deg`
+//! shows a temperature in its own unit with just `°`.
+//!
+//! If the `locale` option is set, the decimal separator and digit grouping of that locale are used.
+//!
+//! ## `fix` - Format numbers using fixed-point notation
+//!
+//! Takes the same arguments as `eng`, except that the first one sets the number of digits after
+//! the decimal point (`1` by default). The prefix is not chosen automatically, so the number is
+//! shown as is unless a prefix is given, e.g. `$used.fix(1,B,Gi)` always shows gibibytes. Units
+//! are converted and the `locale` is applied like with `eng`.
+//!
+//! ## `plural` - Choose a word depending on a number
+//!
+//! Takes one word per plural form of the language set with the `locale` option (English if not
+//! set), following [CLDR](https://cldr.unicode.org/index/cldr-spec/plural-rules). For example,
+//! English has two forms, `$count.plural(update, updates)`, and Russian has three,
+//! `$count.plural(обновление, обновления, обновлений)`. The number itself is not displayed.
+//!
 //! ## `bar` - Display numbers as progress bars
 //!
 //! Argument | Default value
 //! ---------|--------------
 //! Width - the width of the bar (in characters) | `5`
 //! Max value - which value is treated as "full". For example, for battery level `100` is full. | `100`
+//! Min value - which value is treated as "empty" | `0`
+//!
+//! Some blocks (`sound`, `backlight`, `xrandr`, `hueshift` and `music`) let you click on a bar to
+//! set the value, e.g. clicking in the middle of `$volume.bar(10)` sets the volume to 50%, and
+//! clicking in the middle of `$volume.bar(10, 150)` sets it to 75%.
+//!
+//! ## `icon` - Display numbers as icons
+//!
+//! Picks an icon from an icon family, which is a list of icons for increasing values defined in
+//! the icon set (e.g. `backlight = ["...", "...", "..."]`). The range between the min and max
+//! values is split evenly between the icons of the family.
+//!
+//! Argument | Default value
+//! ---------|--------------
+//! Family - the name of the icon family | -
+//! Min value - the value which selects the first icon | `0`
+//! Max value - the value which selects the last icon | `100`
+//!
+//! For example, `$volume.icon(volume)` shows the `volume` family by volume level and
+//! `$temperature.icon(temp, 40, 90)` could show a thermometer filling up from 40 to 90 degrees.
+//!
+//! ## `graph` - Display the history of a number as a sparkline
+//!
+//! The history is kept across updates of the block, so the graph moves every time the block
+//! updates (not every time it is redrawn). Inside the body of a list (`$list{...}`) there is no
+//! history, and the graph only shows the current value of each item.
+//!
+//! Argument | Default value
+//! ---------|--------------
+//! Width - the width of the graph (in characters) | `8`
+//! Min value - the value displayed as an empty bar, or `auto` to use the smallest value in the history | `auto`
+//! Max value - the value displayed as a full bar, or `auto` to use the largest value in the history | `auto`
+//! Style - `blocks` (one value per character) or `braille` (two values per character) | `blocks`
+//!
+//! For example, `$utilization.graph(20, 0, 100, braille)` displays the last 40 values of CPU
+//! utilization.
+//!
+//! # Text transformations
+//!
+//! The following formatters transform text. They can be followed by another formatter, for
+//! example `$title.re(" — Mozilla Firefox$").str(0, 20)`. If not followed by a formatter, `str()`
+//! is used.
+//!
+//! ## `re` - Replace all matches of a regular expression
+//!
+//! Argument | Default value
+//! ---------|--------------
+//! Pattern - a [regular expression](https://docs.rs/regex/latest/regex/#syntax) | N/A
+//! Replacement - the text to replace matches with. `$1`, `$2`, etc. refer to capture groups | `""`
+//!
+//! ## `map` - Map values to other values
+//!
+//! Takes any number of `key=value` arguments. If the text is equal to one of the keys, it is
+//! replaced with the corresponding value. An argument without `=` sets the default value for the
+//! text that matches none of the keys. If no default is set, the text is left as is. Arguments
+//! which contain `,` or `)` have to be quoted or escaped with `\`. For example:
+//! `$layout.map("English (US)=us", "Bulgarian (new phonetic)=bg", ??)` or
+//! `$layout.map(English (US\)=us, ??)`.
+//!
+//! ## `upper` and `lower` - Change the case of text
+//!
+//! These formatters take no arguments.
 //!
 //! # Handling missing placeholders and incorrect types
 //!
@@ -67,6 +158,10 @@
 //! always displayed, followed by the actual percentage or "N/A" in case percentage is not
 //! available. This example does exactly the same thing as `Percentage: $percentage|Percentage: N/A`
 //!
+//! Since the placeholders a block provides and their types are known, the configured formats
+//! (`format`, `format_alt`, `missing_format` and so on) are checked when a block is started. Unknown placeholders and formatters which cannot be applied to a
+//! placeholder's type (e.g. `eng` on text) are reported as warnings on stderr.
+//!
 //! # How to use flags
 //!
 //! Some blocks provide flags, which can be used to change the format based on some critera. For
@@ -76,9 +171,28 @@
 //! ```text
 //! $a{a is set}|$b$c{b and c are set}|${b|c}{b or c is set}|neither flag is set
 //! ```
+//!
+//! # How to use lists
+//!
+//! Some blocks provide lists, for example [cpu](crate::blocks::cpu)'s `cores`. Each item of a list
+//! has its own set of placeholders. A list is displayed by placing a format right after it, which
+//! will be rendered once for every item. Optionally, `.join(<separator>)` can be used to separate
+//! the items:
+//!
+//! ```text
+//! $cores{$utilization.bar(1)}
+//! $cores{$core: $utilization}.join(", ")
+//! ```
+//!
+//! Each item is shown as its own i3bar block, whose `instance` is the item's position in the list
+//! (starting at 0), so clicks on an item can be told apart.
+//!
+//! Formatter arguments may be enclosed in double quotes, in which case `,` and `)` do not need to
+//! be escaped.
 
 pub mod config;
 pub mod formatter;
+pub mod locale;
 pub mod prefix;
 pub mod scheduling;
 pub mod template;
@@ -92,10 +206,14 @@ use std::sync::Arc;
 use crate::config::SharedConfig;
 use crate::errors::*;
 use template::FormatTemplate;
-use value::Value;
+use value::{Value, ValueType};
 
 pub type Values = HashMap<Cow<'static, str>, Value>;
 
+/// Names and types of the placeholders a block provides. A name ending with `<N>` (e.g.
+/// `"utilization<N>"`) matches that prefix followed by a number.
+pub type Placeholders = [(&'static str, ValueType)];
+
 pub type Format = Arc<FormatInner>;
 
 #[derive(Debug)]
@@ -114,6 +232,22 @@ impl FormatInner {
         self.intervals.clone()
     }
 
+    /// Check the templates against the placeholders provided by a block. Returns a list of
+    /// human-readable warnings.
+    pub fn validate(&self, placeholders: &Placeholders) -> Vec<String> {
+        let mut warnings = Vec::new();
+        self.full.validate(placeholders, &mut warnings);
+        self.short.validate(placeholders, &mut warnings);
+        warnings.dedup();
+        warnings
+    }
+
+    /// Must be called every time the values change, so formatters like `graph` can track them
+    pub fn update(&self, values: &Values) {
+        self.full.update(values);
+        self.short.update(values);
+    }
+
     pub fn render(
         &self,
         values: &Values,
@@ -157,11 +291,13 @@ impl Fragment {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct Metadata {
     pub instance: Option<usize>,
     pub underline: bool,
     pub italic: bool,
+    /// The values of an empty and a full slider, if this is a slider (see `Value::with_slider`)
+    pub slider: Option<(f64, f64)>,
 }
 
 impl Metadata {
@@ -1,26 +1,44 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::fmt::Debug;
-use std::iter::repeat;
 use std::str::FromStr;
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
 use super::prefix::Prefix;
 use super::unit::Unit;
 use super::value::ValueInner as Value;
+use super::value::ValueType;
+use crate::config::SharedConfig;
 use crate::errors::*;
 use crate::escape::CollectEscaped;
 
 const DEFAULT_STR_MIN_WIDTH: usize = 0;
 const DEFAULT_STR_MAX_WIDTH: Option<usize> = None;
+const DEFAULT_STR_ALIGN: Align = Align::Left;
+const DEFAULT_STR_ELLIPSIS: &str = "";
 
 const DEFAULT_STRROT_WIDTH: usize = 15;
 const DEFAULT_STRROT_INTERVAL: f64 = 0.5;
 
 const DEFAULT_BAR_WIDTH: usize = 5;
 const DEFAULT_BAR_MAX_VAL: f64 = 100.0;
+const DEFAULT_BAR_MIN_VAL: f64 = 0.0;
+
+const DEFAULT_GRAPH_WIDTH: usize = 8;
+
+const DEFAULT_ICON_MIN_VAL: f64 = 0.0;
+const DEFAULT_ICON_MAX_VAL: f64 = 100.0;
 
 pub const DEFAULT_STRING_FORMATTER: StrFormatter = StrFormatter {
     min_width: DEFAULT_STR_MIN_WIDTH,
     max_width: DEFAULT_STR_MAX_WIDTH,
+    align: DEFAULT_STR_ALIGN,
+    ellipsis: Cow::Borrowed(DEFAULT_STR_ELLIPSIS),
 };
 
 // TODO: split those defaults
@@ -43,6 +61,8 @@ pub const DEFAULT_FLAG_FORMATTER: FlagFormatter = FlagFormatter;
 enum StrArgs {
     MinWidth,
     MaxWidth,
+    Align,
+    Ellipsis,
 }
 
 enum RotStrArgs {
@@ -53,6 +73,20 @@ enum RotStrArgs {
 enum BarArgs {
     Width,
     MaxValue,
+    MinValue,
+}
+
+enum IconArgs {
+    Family,
+    MinValue,
+    MaxValue,
+}
+
+enum GraphArgs {
+    Width,
+    Min,
+    Max,
+    Style,
 }
 
 enum EngFixArgs {
@@ -62,26 +96,91 @@ enum EngFixArgs {
 }
 
 pub trait Formatter: Debug + Send + Sync {
-    fn format(&self, val: &Value) -> Result<String>;
+    fn format(&self, val: &Value, config: &SharedConfig) -> Result<String>;
 
     fn interval(&self) -> Option<Duration> {
         None
     }
+
+    /// Called every time the block sets new values. Formatters that keep some state across
+    /// updates (e.g. `graph`) should record the new value here.
+    fn update(&self, _val: &Value) {}
+
+    /// Check whether values of type `ty` can be formatted. On failure, returns the name of the
+    /// formatter which does not support this type.
+    fn check_type(&self, ty: ValueType) -> Result<(), &'static str>;
+
+    /// If the output is drawn in proportion to the value, the values of an empty and a full
+    /// output, so that the position of a click within it can be mapped back to a value (see
+    /// `Value::with_slider`)
+    fn slider_range(&self) -> Option<(f64, f64)> {
+        None
+    }
+}
+
+/// Whether the formatter transforms text and thus can be followed by another formatter
+pub fn is_transformation(name: &str) -> bool {
+    matches!(name, "re" | "map" | "upper" | "lower")
+}
+
+/// Create a formatter from a chain like `$title.re(...).upper().str(0, 10)`. All formatters but the
+/// last one must be transformations.
+pub fn new_formatter_chain(chain: &[(String, Vec<String>)]) -> Result<Box<dyn Formatter>> {
+    let ((last_name, last_args), rest) = chain.split_last().error("Empty formatter chain")?;
+    if rest.is_empty() {
+        return new_formatter(last_name, last_args);
+    }
+    let mut transformations = rest
+        .iter()
+        .map(|(name, args)| {
+            Transformation::new(name, args)?.or_error(|| {
+                format!(
+                    "'{}' formatter cannot be followed by another formatter",
+                    name
+                )
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let formatter = match Transformation::new(last_name, last_args)? {
+        Some(t) => {
+            transformations.push(t);
+            None
+        }
+        None => Some(new_formatter(last_name, last_args)?),
+    };
+    Ok(Box::new(TransformFormatter {
+        transformations,
+        formatter,
+    }))
 }
 
 pub fn new_formatter(name: &str, args: &[String]) -> Result<Box<dyn Formatter>> {
+    if let Some(transformation) = Transformation::new(name, args)? {
+        return Ok(Box::new(TransformFormatter {
+            transformations: vec![transformation],
+            formatter: None,
+        }));
+    }
     match name {
         "str" => {
             let min_width: usize = match args.get(StrArgs::MinWidth as usize) {
-                Some(v) => v.parse().error("Width must be a positive integer")?,
+                Some(v) => v.trim().parse().error("Width must be a positive integer")?,
                 None => DEFAULT_STR_MIN_WIDTH,
             };
             let max_width: Option<usize> =
-                match args.get(StrArgs::MaxWidth as usize).map(|x| x.as_str()) {
+                match args.get(StrArgs::MaxWidth as usize).map(|x| x.trim()) {
                     Some("inf") => None,
                     Some(v) => Some(v.parse().error("Width must be a positive integer")?),
                     None => DEFAULT_STR_MAX_WIDTH,
                 };
+            let align = match args.get(StrArgs::Align as usize) {
+                Some(v) => v.trim().parse()?,
+                None => DEFAULT_STR_ALIGN,
+            };
+            let ellipsis = match args.get(StrArgs::Ellipsis as usize) {
+                Some(v) => Cow::Owned(v.clone()),
+                None => Cow::Borrowed(DEFAULT_STR_ELLIPSIS),
+            };
             if let Some(max_width) = max_width {
                 if max_width < min_width {
                     return Err(Error::new(
@@ -92,11 +191,13 @@ pub fn new_formatter(name: &str, args: &[String]) -> Result<Box<dyn Formatter>>
             Ok(Box::new(StrFormatter {
                 min_width,
                 max_width,
+                align,
+                ellipsis,
             }))
         }
         "rot-str" => {
             let width: usize = match args.get(RotStrArgs::Width as usize) {
-                Some(v) => v.parse().error("Width must be a positive integer")?,
+                Some(v) => v.trim().parse().error("Width must be a positive integer")?,
                 None => DEFAULT_STRROT_WIDTH,
             };
             let interval: f64 = match args.get(RotStrArgs::Interval as usize) {
@@ -124,40 +225,190 @@ pub fn new_formatter(name: &str, args: &[String]) -> Result<Box<dyn Formatter>>
                 Some(v) => v.parse().error("Max value must be a number")?,
                 None => DEFAULT_BAR_MAX_VAL,
             };
-            Ok(Box::new(BarFormatter { width, max_value }))
+            let min_value: f64 = match args.get(BarArgs::MinValue as usize) {
+                Some(v) => v.parse().error("Min value must be a number")?,
+                None => DEFAULT_BAR_MIN_VAL,
+            };
+            if max_value <= min_value {
+                return Err(Error::new("Max value must be greater than min value"));
+            }
+            Ok(Box::new(BarFormatter {
+                width,
+                max_value,
+                min_value,
+            }))
+        }
+        "icon" => {
+            let family = args
+                .get(IconArgs::Family as usize)
+                .error("The name of the icon family is required")?
+                .trim()
+                .to_string();
+            let parse_bound = |arg: IconArgs, default: f64| -> Result<f64> {
+                match args.get(arg as usize) {
+                    Some(v) => v.trim().parse().error("Min and max must be numbers"),
+                    None => Ok(default),
+                }
+            };
+            let min = parse_bound(IconArgs::MinValue, DEFAULT_ICON_MIN_VAL)?;
+            let max = parse_bound(IconArgs::MaxValue, DEFAULT_ICON_MAX_VAL)?;
+            if max <= min {
+                return Err(Error::new("Max value must be greater than min value"));
+            }
+            Ok(Box::new(IconFormatter { family, min, max }))
+        }
+        "graph" => {
+            let width: usize = match args.get(GraphArgs::Width as usize) {
+                Some(v) => v.trim().parse().error("Width must be a positive integer")?,
+                None => DEFAULT_GRAPH_WIDTH,
+            };
+            if width == 0 {
+                return Err(Error::new("Width must be a positive integer"));
+            }
+            let parse_bound = |arg: GraphArgs| -> Result<Option<f64>> {
+                match args.get(arg as usize).map(|x| x.trim()) {
+                    None | Some("auto") => Ok(None),
+                    Some(v) => Ok(Some(
+                        v.parse().error("Min and max must be numbers or 'auto'")?,
+                    )),
+                }
+            };
+            let min = parse_bound(GraphArgs::Min)?;
+            let max = parse_bound(GraphArgs::Max)?;
+            let style = match args.get(GraphArgs::Style as usize).map(|x| x.trim()) {
+                None | Some("blocks") => GraphStyle::Blocks,
+                Some("braille") => GraphStyle::Braille,
+                Some(x) => return Err(Error::new(format!("Unknown graph style: '{}'", x))),
+            };
+            Ok(Box::new(GraphFormatter {
+                width,
+                min,
+                max,
+                style,
+                history: Mutex::new(VecDeque::new()),
+            }))
+        }
+        "eng" => Ok(Box::new(EngFormatter(EngFixConfig::from_args(args, 3)?))),
+        "fix" => Ok(Box::new(FixFormatter(EngFixConfig::from_args(args, 1)?))),
+        "plural" => {
+            if args.is_empty() {
+                return Err(Error::new("'plural' requires at least one argument"));
+            }
+            Ok(Box::new(PluralFormatter {
+                forms: args
+                    .iter()
+                    .map(|form| unquote(form.trim()).to_string())
+                    .collect(),
+            }))
         }
-        "eng" => Ok(Box::new(EngFormatter(EngFixConfig::from_args(args)?))),
-        "fix" => Ok(Box::new(FixFormatter(EngFixConfig::from_args(args)?))),
         _ => Err(Error::new(format!("Unknown formatter: '{}'", name))),
     }
 }
 
+/// Text alignment, used by `str` formatter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+impl FromStr for Align {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "l" | "left" => Ok(Self::Left),
+            "c" | "center" | "centre" => Ok(Self::Center),
+            "r" | "right" => Ok(Self::Right),
+            x => Err(Error::new(format!("Unknown alignment: '{}'", x))),
+        }
+    }
+}
+
+/// The number of columns `text` occupies in a terminal-like environment
+fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Truncate `text` to fit in `max_width` columns. The `ellipsis` is appended if text was truncated.
+fn truncate<'a>(text: &'a str, max_width: usize, ellipsis: &str) -> Cow<'a, str> {
+    if display_width(text) <= max_width {
+        return Cow::Borrowed(text);
+    }
+    let ellipsis = if display_width(ellipsis) <= max_width {
+        ellipsis
+    } else {
+        ""
+    };
+    let mut retval = String::new();
+    let mut width = display_width(ellipsis);
+    for grapheme in text.graphemes(true) {
+        width += display_width(grapheme);
+        if width > max_width {
+            break;
+        }
+        retval.push_str(grapheme);
+    }
+    retval.push_str(ellipsis);
+    Cow::Owned(retval)
+}
+
+/// Pad `text` with spaces to be at least `min_width` columns wide
+fn pad(text: &str, min_width: usize, align: Align) -> String {
+    let missing = min_width.saturating_sub(display_width(text));
+    let (left, right) = match align {
+        Align::Left => (0, missing),
+        Align::Center => (missing / 2, missing - missing / 2),
+        Align::Right => (missing, 0),
+    };
+    let mut retval = String::with_capacity(text.len() + missing);
+    retval.push_str(&" ".repeat(left));
+    retval.push_str(text);
+    retval.push_str(&" ".repeat(right));
+    retval
+}
+
 #[derive(Debug)]
 pub struct StrFormatter {
     min_width: usize,
     max_width: Option<usize>,
+    align: Align,
+    ellipsis: Cow<'static, str>,
 }
 
 impl Formatter for StrFormatter {
-    fn format(&self, val: &Value) -> Result<String> {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String> {
         match val {
             Value::Text(text) => {
-                let width = text.chars().count();
-                Ok(text
+                let text = match self.max_width {
+                    Some(max_width) => truncate(text, max_width, &self.ellipsis),
+                    None => Cow::Borrowed(text.as_str()),
+                };
+                Ok(pad(&text, self.min_width, self.align)
                     .chars()
-                    .chain(repeat(' ').take(self.min_width.saturating_sub(width)))
-                    .take(self.max_width.unwrap_or(usize::MAX))
                     .collect_pango())
             }
             Value::Icon(icon) => Ok(icon.clone()), // No escaping
             Value::Number { .. } => Err(Error::new_format(
                 "A number cannot be formatted with 'str' formatter",
             )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'str' formatter",
+            )),
             Value::Flag => Err(Error::new_format(
                 "A flag cannot be formatted with 'str' formatter",
             )),
         }
     }
+
+    fn check_type(&self, ty: ValueType) -> Result<(), &'static str> {
+        if matches!(ty, ValueType::Text | ValueType::Icon) {
+            Ok(())
+        } else {
+            Err("str")
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -168,29 +419,29 @@ pub struct RotStrFormatter {
 }
 
 impl Formatter for RotStrFormatter {
-    fn format(&self, val: &Value) -> Result<String> {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String> {
         match val {
             Value::Text(text) => {
-                let full_width = text.chars().count();
-                if full_width <= self.width {
-                    Ok(text
-                        .chars()
-                        .chain(repeat(' '))
-                        .take(self.width)
-                        .collect_pango())
+                if display_width(text) <= self.width {
+                    Ok(pad(text, self.width, Align::Left).chars().collect_pango())
                 } else {
-                    let full_width = full_width + 1; // Now we include '|' at the end
+                    // Now we include '|' at the end
+                    let graphemes: Vec<&str> = text.graphemes(true).chain(Some("|")).collect();
                     let step = (self.init_time.elapsed().as_millis() as u64 / self.interval)
                         as usize
-                        % full_width;
-                    let w1 = self.width.min(full_width - step);
-                    Ok(text
+                        % graphemes.len();
+                    let mut retval = String::new();
+                    let mut width = 0;
+                    for grapheme in graphemes.iter().cycle().skip(step) {
+                        width += display_width(grapheme);
+                        if width > self.width {
+                            break;
+                        }
+                        retval.push_str(grapheme);
+                    }
+                    // A wide character might not fit at the end
+                    Ok(pad(&retval, self.width, Align::Left)
                         .chars()
-                        .chain(Some('|'))
-                        .skip(step)
-                        .take(w1)
-                        .chain(text.chars())
-                        .take(self.width)
                         .collect_pango())
                 }
             }
@@ -200,6 +451,9 @@ impl Formatter for RotStrFormatter {
             Value::Number { .. } => Err(Error::new_format(
                 "A number cannot be formatted with 'rot-str' formatter",
             )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'rot-str' formatter",
+            )),
             Value::Flag => Err(Error::new_format(
                 "A flag cannot be formatted with 'rot-str' formatter",
             )),
@@ -209,12 +463,61 @@ impl Formatter for RotStrFormatter {
     fn interval(&self) -> Option<Duration> {
         Some(Duration::from_millis(self.interval))
     }
+
+    fn check_type(&self, ty: ValueType) -> Result<(), &'static str> {
+        if matches!(ty, ValueType::Text) {
+            Ok(())
+        } else {
+            Err("rot-str")
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct IconFormatter {
+    family: String,
+    min: f64,
+    max: f64,
+}
+
+impl Formatter for IconFormatter {
+    fn format(&self, val: &Value, config: &SharedConfig) -> Result<String> {
+        match val {
+            Value::Number { val, .. } => {
+                let fraction = (val - self.min) / (self.max - self.min);
+                config
+                    .get_icon_in_family(&self.family, fraction)
+                    .or_error(|| format!("Icon family '{}' not found", self.family))
+            }
+            Value::Text(_) => Err(Error::new_format(
+                "Text cannot be formatted with 'icon' formatter",
+            )),
+            Value::Icon(_) => Err(Error::new_format(
+                "An icon cannot be formatted with 'icon' formatter",
+            )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'icon' formatter",
+            )),
+            Value::Flag => Err(Error::new_format(
+                "A flag cannot be formatted with 'icon' formatter",
+            )),
+        }
+    }
+
+    fn check_type(&self, ty: ValueType) -> Result<(), &'static str> {
+        if matches!(ty, ValueType::Number) {
+            Ok(())
+        } else {
+            Err("icon")
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct BarFormatter {
     width: usize,
     max_value: f64,
+    min_value: f64,
 }
 
 const VERTICAL_BAR_CHARS: [char; 9] = [
@@ -223,10 +526,10 @@ const VERTICAL_BAR_CHARS: [char; 9] = [
 ];
 
 impl Formatter for BarFormatter {
-    fn format(&self, val: &Value) -> Result<String> {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String> {
         match val {
             Value::Number { mut val, .. } => {
-                val = (val / self.max_value).clamp(0., 1.);
+                val = ((val - self.min_value) / (self.max_value - self.min_value)).clamp(0., 1.);
                 let chars_to_fill = val * self.width as f64;
                 Ok((0..self.width)
                     .map(|i| {
@@ -240,11 +543,299 @@ impl Formatter for BarFormatter {
             Value::Icon(_) => Err(Error::new_format(
                 "An icon cannot be formatted with 'bar' formatter",
             )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'bar' formatter",
+            )),
             Value::Flag => Err(Error::new_format(
                 "A flag cannot be formatted with 'bar' formatter",
             )),
         }
     }
+
+    fn check_type(&self, ty: ValueType) -> Result<(), &'static str> {
+        if matches!(ty, ValueType::Number) {
+            Ok(())
+        } else {
+            Err("bar")
+        }
+    }
+
+    fn slider_range(&self) -> Option<(f64, f64)> {
+        Some((self.min_value, self.max_value))
+    }
+}
+
+#[derive(Debug)]
+enum Transformation {
+    /// `re(pattern, replacement)` - replace all matches of a regex
+    Replace { re: Regex, replacement: String },
+    /// `map(key=value, ..., default)` - replace the whole text if it matches a key
+    Map {
+        map: Vec<(String, String)>,
+        default: Option<String>,
+    },
+    /// `upper()`
+    Upper,
+    /// `lower()`
+    Lower,
+}
+
+impl Transformation {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Replace { .. } => "re",
+            Self::Map { .. } => "map",
+            Self::Upper => "upper",
+            Self::Lower => "lower",
+        }
+    }
+
+    fn new(name: &str, args: &[String]) -> Result<Option<Self>> {
+        Ok(Some(match name {
+            "re" => {
+                let pattern = args.first().error("'re' requires a pattern")?;
+                if args.len() > 2 {
+                    return Err(Error::new("'re' takes at most two arguments"));
+                }
+                Self::Replace {
+                    re: Regex::new(pattern)
+                        .or_error(|| format!("'{}' is not a valid regex", pattern))?,
+                    replacement: args.get(1).cloned().unwrap_or_default(),
+                }
+            }
+            "map" => {
+                let mut map = Vec::new();
+                let mut default = None;
+                for arg in args {
+                    match arg.split_once('=') {
+                        Some((key, val)) => {
+                            map.push((
+                                unquote(key.trim()).to_string(),
+                                unquote(val.trim()).to_string(),
+                            ));
+                        }
+                        None if default.is_none() => {
+                            default = Some(unquote(arg.trim()).to_string());
+                        }
+                        None => return Err(Error::new("'map' accepts only one default value")),
+                    }
+                }
+                Self::Map { map, default }
+            }
+            "upper" => Self::Upper,
+            "lower" => Self::Lower,
+            _ => return Ok(None),
+        }))
+    }
+
+    fn apply(&self, text: &str) -> String {
+        match self {
+            Self::Replace { re, replacement } => {
+                re.replace_all(text, replacement.as_str()).into_owned()
+            }
+            Self::Map { map, default } => map
+                .iter()
+                .find(|(key, _)| key == text)
+                .map(|(_, val)| val)
+                .or(default.as_ref())
+                .map_or_else(|| text.to_string(), Clone::clone),
+            Self::Upper => text.to_uppercase(),
+            Self::Lower => text.to_lowercase(),
+        }
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+/// Applies text transformations, then formats the result using `formatter` (or `str` if not set)
+#[derive(Debug)]
+pub struct TransformFormatter {
+    transformations: Vec<Transformation>,
+    formatter: Option<Box<dyn Formatter>>,
+}
+
+impl TransformFormatter {
+    fn transform(&self, val: &Value) -> Result<Value> {
+        let apply = |text: &str| {
+            self.transformations
+                .iter()
+                .fold(text.to_string(), |text, t| t.apply(&text))
+        };
+        match val {
+            Value::Text(text) => Ok(Value::Text(apply(text))),
+            Value::Icon(icon) => Ok(Value::Icon(apply(icon))),
+            Value::Number { .. } => Err(Error::new_format(
+                "A number cannot be formatted with text transformation formatters",
+            )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with text transformation formatters",
+            )),
+            Value::Flag => Err(Error::new_format(
+                "A flag cannot be formatted with text transformation formatters",
+            )),
+        }
+    }
+}
+
+impl Formatter for TransformFormatter {
+    fn format(&self, val: &Value, config: &SharedConfig) -> Result<String> {
+        let val = self.transform(val)?;
+        match &self.formatter {
+            Some(formatter) => formatter.format(&val, config),
+            None => DEFAULT_STRING_FORMATTER.format(&val, config),
+        }
+    }
+
+    fn interval(&self) -> Option<Duration> {
+        self.formatter.as_ref().and_then(|f| f.interval())
+    }
+
+    fn update(&self, val: &Value) {
+        if let (Some(formatter), Ok(val)) = (&self.formatter, self.transform(val)) {
+            formatter.update(&val);
+        }
+    }
+
+    fn check_type(&self, ty: ValueType) -> Result<(), &'static str> {
+        if !matches!(ty, ValueType::Text | ValueType::Icon) {
+            return Err(self.transformations[0].name());
+        }
+        match &self.formatter {
+            Some(formatter) => formatter.check_type(ty),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum GraphStyle {
+    /// One value per character using block elements
+    Blocks,
+    /// Two values per character using braille patterns
+    Braille,
+}
+
+#[derive(Debug)]
+pub struct GraphFormatter {
+    width: usize,
+    min: Option<f64>,
+    max: Option<f64>,
+    style: GraphStyle,
+    history: Mutex<VecDeque<f64>>,
+}
+
+const GRAPH_BLOCK_CHARS: [char; 8] = [
+    '\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}',
+];
+
+// Braille dots of the left and the right columns, from bottom to top
+const BRAILLE_LEFT_DOTS: [u32; 4] = [0x40, 0x04, 0x02, 0x01];
+const BRAILLE_RIGHT_DOTS: [u32; 4] = [0x80, 0x20, 0x10, 0x08];
+
+impl GraphFormatter {
+    fn capacity(&self) -> usize {
+        match self.style {
+            GraphStyle::Blocks => self.width,
+            GraphStyle::Braille => self.width * 2,
+        }
+    }
+
+    /// Scale `val` to `0..=1` range
+    fn normalize(&self, val: f64, min: f64, max: f64) -> f64 {
+        let range = max - min;
+        if range > 0. {
+            ((val - min) / range).clamp(0., 1.)
+        } else {
+            0.
+        }
+    }
+}
+
+impl Formatter for GraphFormatter {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String> {
+        match val {
+            Value::Number { val, .. } => {
+                let history = self.history.lock().unwrap();
+                let current = [*val];
+                let hist: Vec<f64> = if history.is_empty() {
+                    current.to_vec()
+                } else {
+                    history.iter().copied().collect()
+                };
+
+                let min = self
+                    .min
+                    .unwrap_or_else(|| hist.iter().copied().fold(f64::INFINITY, f64::min));
+                let max = self
+                    .max
+                    .unwrap_or_else(|| hist.iter().copied().fold(f64::NEG_INFINITY, f64::max));
+                let missing = self.capacity() - hist.len();
+
+                Ok(match self.style {
+                    GraphStyle::Blocks => {
+                        let mut graph = " ".repeat(missing);
+                        graph.extend(hist.iter().map(|&x| {
+                            GRAPH_BLOCK_CHARS[(self.normalize(x, min, max) * 7.).round() as usize]
+                        }));
+                        graph
+                    }
+                    GraphStyle::Braille => {
+                        // `None` is an empty column
+                        let mut columns: Vec<Option<usize>> = vec![None; missing];
+                        columns.extend(hist.iter().map(|&x| {
+                            Some((self.normalize(x, min, max) * 3.).round() as usize + 1)
+                        }));
+                        columns
+                            .chunks(2)
+                            .map(|pair| {
+                                let dots = |col: Option<usize>, dots: &[u32; 4]| {
+                                    dots.iter().take(col.unwrap_or(0)).sum::<u32>()
+                                };
+                                let code = 0x2800
+                                    + dots(pair[0], &BRAILLE_LEFT_DOTS)
+                                    + dots(pair.get(1).copied().flatten(), &BRAILLE_RIGHT_DOTS);
+                                char::from_u32(code).unwrap_or(' ')
+                            })
+                            .collect()
+                    }
+                })
+            }
+            Value::Text(_) => Err(Error::new_format(
+                "Text cannot be formatted with 'graph' formatter",
+            )),
+            Value::Icon(_) => Err(Error::new_format(
+                "An icon cannot be formatted with 'graph' formatter",
+            )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'graph' formatter",
+            )),
+            Value::Flag => Err(Error::new_format(
+                "A flag cannot be formatted with 'graph' formatter",
+            )),
+        }
+    }
+
+    fn update(&self, val: &Value) {
+        if let Value::Number { val, .. } = val {
+            let mut history = self.history.lock().unwrap();
+            if history.len() == self.capacity() {
+                history.pop_front();
+            }
+            history.push_back(*val);
+        }
+    }
+
+    fn check_type(&self, ty: ValueType) -> Result<(), &'static str> {
+        if matches!(ty, ValueType::Number) {
+            Ok(())
+        } else {
+            Err("graph")
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -334,14 +925,14 @@ struct EngFixConfig {
 }
 
 impl EngFixConfig {
-    fn from_args(args: &[String]) -> Result<Self> {
+    fn from_args(args: &[String], default_width: usize) -> Result<Self> {
         Ok(Self {
             width: args
                 .get(EngFixArgs::Width as usize)
                 .map(|x| x.parse::<usize>())
                 .transpose()
                 .error("Width must be a positive integer")?
-                .unwrap_or(3),
+                .unwrap_or(default_width),
             unit: args
                 .get(EngFixArgs::Unit as usize)
                 .map(|x| x.parse::<UnitConfig>())
@@ -354,19 +945,44 @@ impl EngFixConfig {
                 .unwrap_or_default(),
         })
     }
+
+    /// Convert the value to the configured unit, if any
+    fn convert(&self, val: f64, unit: Unit) -> Result<(f64, Unit)> {
+        match self.unit.unit {
+            Some(new_unit) => Ok((unit.convert(val, new_unit)?, new_unit)),
+            None => Ok((val, unit)),
+        }
+    }
+
+    /// Append the prefix and the unit to the formatted number, unless they are hidden
+    fn push_suffix(&self, retval: &mut String, prefix: Prefix, unit: Unit) {
+        let display_prefix =
+            !self.prefix.hidden && prefix != Prefix::One && prefix != Prefix::OneButBinary;
+        let display_unit = !self.unit.hidden && unit != Unit::None;
+
+        if display_prefix {
+            if self.prefix.has_space {
+                retval.push(' ');
+            }
+            retval.push_str(&prefix.to_string());
+        }
+        if display_unit {
+            if self.unit.has_space || (self.prefix.has_space && !display_prefix) {
+                retval.push(' ');
+            }
+            retval.push_str(&unit.to_string());
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct EngFormatter(EngFixConfig);
 
 impl Formatter for EngFormatter {
-    fn format(&self, val: &Value) -> Result<String> {
+    fn format(&self, val: &Value, config: &SharedConfig) -> Result<String> {
         match val {
-            Value::Number { mut val, mut unit } => {
-                if let Some(new_unit) = self.0.unit.unit {
-                    val = unit.convert(val, new_unit)?;
-                    unit = new_unit;
-                }
+            Value::Number { val, unit } => {
+                let (mut val, unit) = self.0.convert(*val, *unit)?;
 
                 let (min_prefix, max_prefix) = match self.0.prefix.prefix {
                     Some((prefix, true)) => (prefix, prefix),
@@ -393,24 +1009,10 @@ impl Formatter for EngFormatter {
                     1 => format!(" {}", val.floor() as i64),
                     rest => format!("{:.*}", rest as usize - 1, val),
                 };
-
-                let display_prefix = !self.0.prefix.hidden
-                    && prefix != Prefix::One
-                    && prefix != Prefix::OneButBinary;
-                let display_unit = !self.0.unit.hidden && unit != Unit::None;
-
-                if display_prefix {
-                    if self.0.prefix.has_space {
-                        retval.push(' ');
-                    }
-                    retval.push_str(&prefix.to_string());
-                }
-                if display_unit {
-                    if self.0.unit.has_space || (self.0.prefix.has_space && !display_prefix) {
-                        retval.push(' ');
-                    }
-                    retval.push_str(&unit.to_string());
+                if let Some(locale) = &config.locale {
+                    retval = locale.localize_number(&retval);
                 }
+                self.0.push_suffix(&mut retval, prefix, unit);
 
                 Ok(retval)
             }
@@ -420,46 +1022,272 @@ impl Formatter for EngFormatter {
             Value::Icon(_) => Err(Error::new_format(
                 "An icon cannot be formatted with 'eng' formatter",
             )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'eng' formatter",
+            )),
             Value::Flag => Err(Error::new_format(
                 "A flag cannot be formatted with 'eng' formatter",
             )),
         }
     }
+
+    fn check_type(&self, ty: ValueType) -> Result<(), &'static str> {
+        if matches!(ty, ValueType::Number) {
+            Ok(())
+        } else {
+            Err("eng")
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct FixFormatter(EngFixConfig);
 
 impl Formatter for FixFormatter {
-    fn format(&self, val: &Value) -> Result<String> {
+    fn format(&self, val: &Value, config: &SharedConfig) -> Result<String> {
         match val {
-            Value::Number {
-                ..
-                // mut val,
-                // unit,
-                // icon,
-            } => Err(Error::new_format("'fix' formatter is not implemented yet")),
+            Value::Number { val, unit } => {
+                let (val, unit) = self.0.convert(*val, *unit)?;
+
+                // Unlike `eng`, the prefix is never chosen automatically
+                let prefix = match self.0.prefix.prefix {
+                    Some((prefix, _)) => unit.clamp_prefix(prefix),
+                    None => Prefix::One,
+                };
+                let mut retval = format!("{:.*}", self.0.width, prefix.apply(val));
+                if let Some(locale) = &config.locale {
+                    retval = locale.localize_number(&retval);
+                }
+                self.0.push_suffix(&mut retval, prefix, unit);
+
+                Ok(retval)
+            }
             Value::Text(_) => Err(Error::new_format(
                 "Text cannot be formatted with 'fix' formatter",
             )),
             Value::Icon(_) => Err(Error::new_format(
                 "An icon cannot be formatted with 'fix' formatter",
             )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'fix' formatter",
+            )),
             Value::Flag => Err(Error::new_format(
                 "A flag cannot be formatted with 'fix' formatter",
             )),
         }
     }
+
+    fn check_type(&self, ty: ValueType) -> Result<(), &'static str> {
+        if matches!(ty, ValueType::Number) {
+            Ok(())
+        } else {
+            Err("fix")
+        }
+    }
+}
+
+/// Selects one of the given words depending on the number and the plural rules of the locale
+#[derive(Debug)]
+pub struct PluralFormatter {
+    forms: Vec<String>,
+}
+
+impl Formatter for PluralFormatter {
+    fn format(&self, val: &Value, config: &SharedConfig) -> Result<String> {
+        match val {
+            Value::Number { val, .. } => Ok(config
+                .locale
+                .unwrap_or_default()
+                .plural(*val, &self.forms)
+                .to_string()),
+            Value::Text(_) => Err(Error::new_format(
+                "Text cannot be formatted with 'plural' formatter",
+            )),
+            Value::Icon(_) => Err(Error::new_format(
+                "An icon cannot be formatted with 'plural' formatter",
+            )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'plural' formatter",
+            )),
+            Value::Flag => Err(Error::new_format(
+                "A flag cannot be formatted with 'plural' formatter",
+            )),
+        }
+    }
+
+    fn check_type(&self, ty: ValueType) -> Result<(), &'static str> {
+        if matches!(ty, ValueType::Number) {
+            Ok(())
+        } else {
+            Err("plural")
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct FlagFormatter;
 
 impl Formatter for FlagFormatter {
-    fn format(&self, val: &Value) -> Result<String> {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String> {
         match val {
-            Value::Number { .. } | Value::Text(_) | Value::Icon(_) => unreachable!(),
+            Value::Number { .. } | Value::Text(_) | Value::Icon(_) | Value::List(_) => {
+                unreachable!()
+            }
             Value::Flag => Ok(String::new()),
         }
     }
+
+    fn check_type(&self, ty: ValueType) -> Result<(), &'static str> {
+        if matches!(ty, ValueType::Flag) {
+            Ok(())
+        } else {
+            Err("flag")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(args: &[&str], vals: &[f64]) -> String {
+        let args: Vec<String> = args.iter().map(|x| x.to_string()).collect();
+        let f = new_formatter("graph", &args).unwrap();
+        for &val in vals {
+            f.update(&Value::Number {
+                val,
+                unit: Unit::None,
+            });
+        }
+        f.format(
+            &Value::Number {
+                val: 0.,
+                unit: Unit::None,
+            },
+            &SharedConfig::default(),
+        )
+        .unwrap()
+    }
+
+    fn number(name: &str, args: &[&str], val: f64, unit: Unit) -> String {
+        let args: Vec<String> = args.iter().map(|x| x.to_string()).collect();
+        new_formatter(name, &args)
+            .unwrap()
+            .format(&Value::Number { val, unit }, &SharedConfig::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn fix_units() {
+        assert_eq!(number("fix", &[], 1234.56, Unit::None), "1234.6");
+        assert_eq!(number("fix", &["0", "F"], 100., Unit::Celsius), "212°F");
+        assert_eq!(number("fix", &["2", "min"], 90., Unit::Seconds), "1.50min");
+        assert_eq!(
+            number("fix", &["1", "B", "Ki"], 2048., Unit::Bytes),
+            "2.0KiB"
+        );
+    }
+
+    #[test]
+    fn fix_locale() {
+        let config = SharedConfig {
+            locale: Some("de_DE".parse().unwrap()),
+            ..Default::default()
+        };
+        let args = ["2".to_string()];
+        let format = |val| {
+            new_formatter("fix", &args)
+                .unwrap()
+                .format(
+                    &Value::Number {
+                        val,
+                        unit: Unit::None,
+                    },
+                    &config,
+                )
+                .unwrap()
+        };
+        assert_eq!(format(1234567.891), "1.234.567,89");
+        assert_eq!(format(-0.5), "-0,50");
+    }
+
+    fn text(args: &[&str], text: &str) -> String {
+        let args: Vec<String> = args.iter().map(|x| x.to_string()).collect();
+        new_formatter("str", &args)
+            .unwrap()
+            .format(&Value::Text(text.into()), &SharedConfig::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn str_width() {
+        assert_eq!(text(&["5"], "ab"), "ab   ");
+        assert_eq!(text(&["5", "5", "right"], "ab"), "   ab");
+        assert_eq!(text(&["5", "5", "center"], "ab"), " ab  ");
+        assert_eq!(text(&["0", "4"], "abcdef"), "abcd");
+        assert_eq!(text(&["0", "4", "left", "…"], "abcdef"), "abc…");
+        // Wide characters take two columns
+        assert_eq!(text(&["0", "5"], "日本語テキスト"), "日本");
+        assert_eq!(text(&["0", "6", "left", "…"], "日本語テキスト"), "日本…");
+        assert_eq!(text(&["6"], "日本"), "日本  ");
+    }
+
+    fn transform(chain: &[(&str, &[&str])], text: &str) -> String {
+        let chain: Vec<(String, Vec<String>)> = chain
+            .iter()
+            .map(|(name, args)| {
+                (
+                    name.to_string(),
+                    args.iter().map(|x| x.to_string()).collect(),
+                )
+            })
+            .collect();
+        new_formatter_chain(&chain)
+            .unwrap()
+            .format(&Value::Text(text.into()), &SharedConfig::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn transformations() {
+        assert_eq!(
+            transform(
+                &[("re", &[" — Mozilla Firefox$", ""])],
+                "Page — Mozilla Firefox"
+            ),
+            "Page"
+        );
+        assert_eq!(
+            transform(&[("re", &["(\\w+) (\\w+)", "$2 $1"])], "a b"),
+            "b a"
+        );
+        assert_eq!(transform(&[("map", &["us=EN", "de=DE"])], "de"), "DE");
+        assert_eq!(transform(&[("map", &["us=EN", "de=DE"])], "fr"), "fr");
+        assert_eq!(transform(&[("map", &["us=EN", "??"])], "fr"), "??");
+        assert_eq!(transform(&[("upper", &[])], "abc"), "ABC");
+        assert_eq!(
+            transform(
+                &[("lower", &[]), ("str", &["0", "3", "left", "…"])],
+                "ABCDEF"
+            ),
+            "ab…"
+        );
+        assert_eq!(
+            transform(&[("upper", &[]), ("re", &["B", "-"])], "abc"),
+            "A-C"
+        );
+    }
+
+    #[test]
+    fn graph_blocks() {
+        assert_eq!(graph(&["4", "0", "100"], &[0., 100.]), "  ▁█");
+        assert_eq!(graph(&["2", "0", "100"], &[0., 50., 100.]), "▅█");
+        assert_eq!(graph(&["3"], &[1., 2., 3.]), "▁▅█");
+    }
+
+    #[test]
+    fn graph_braille() {
+        assert_eq!(graph(&["1", "0", "100", "braille"], &[0., 100.]), "⣸");
+        assert_eq!(graph(&["2", "0", "100", "braille"], &[100.]), "⠀⢸");
+    }
 }
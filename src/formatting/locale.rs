@@ -0,0 +1,196 @@
+use std::str::FromStr;
+
+use pure_rust_locales::locale_match;
+use serde::de::{self, Deserialize, Deserializer};
+
+use crate::errors::*;
+
+/// Locale-specific conventions used when formatting numbers and plurals
+#[derive(Debug, Clone, Copy)]
+pub struct Locale {
+    locale: chrono::Locale,
+    decimal_point: &'static str,
+    thousands_sep: &'static str,
+    plural_rule: PluralRule,
+}
+
+impl Locale {
+    /// The locale as understood by `chrono`
+    pub fn chrono_locale(&self) -> chrono::Locale {
+        self.locale
+    }
+
+    /// Localize a number formatted with `.` as a decimal point, e.g. `1234.5` becomes `1 234,5`
+    /// in `fr_FR`. Leading whitespace and the sign are preserved. Digits are grouped by three.
+    pub fn localize_number(&self, number: &str) -> String {
+        let digits_start = number
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(number.len());
+        let (sign, rest) = number.split_at(digits_start);
+        let (int, frac) = match rest.split_once('.') {
+            Some((int, frac)) => (int, Some(frac)),
+            None => (rest, None),
+        };
+
+        let mut retval = sign.to_string();
+        for (i, c) in int.chars().enumerate() {
+            if i > 0 && (int.len() - i) % 3 == 0 {
+                retval.push_str(self.thousands_sep);
+            }
+            retval.push(c);
+        }
+        if let Some(frac) = frac {
+            retval.push_str(self.decimal_point);
+            retval.push_str(frac);
+        }
+        retval
+    }
+
+    /// Select one of `forms` for the number `n`. The forms are expected in the order of the
+    /// plural categories of the language, e.g. `one, other` in English and `one, few, many` in
+    /// Russian. If fewer forms are given, the last one is used for the remaining categories.
+    pub fn plural<'a>(&self, n: f64, forms: &'a [String]) -> &'a str {
+        let index = self
+            .plural_rule
+            .category(n)
+            .min(forms.len().saturating_sub(1));
+        forms.get(index).map_or("", String::as_str)
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            locale: chrono::Locale::POSIX,
+            decimal_point: ".",
+            thousands_sep: "",
+            plural_rule: PluralRule::OneOther,
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // Allow names like `de_DE.UTF-8`, as found in `$LANG`
+        let name = s.split('.').next().unwrap_or(s);
+        let locale: chrono::Locale = name
+            .try_into()
+            .ok()
+            .or_error(|| format!("Unknown locale '{s}'"))?;
+        let language = name.split(['_', '@']).next().unwrap_or(name);
+        Ok(Self {
+            locale,
+            decimal_point: locale_match!(locale => LC_NUMERIC::DECIMAL_POINT),
+            thousands_sep: locale_match!(locale => LC_NUMERIC::THOUSANDS_SEP),
+            plural_rule: PluralRule::for_language(language),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Locale {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Plural rules of the most common languages, following CLDR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PluralRule {
+    /// Only one form (e.g. Japanese, Chinese)
+    Single,
+    /// `one` is 1, `other` is everything else (e.g. English, German)
+    OneOther,
+    /// `one` is 0 and 1, `other` is everything else (e.g. French)
+    ZeroOneOther,
+    /// `one` ends with 1 but not 11, `few` ends with 2-4 but not 12-14, `many` is everything else
+    /// (e.g. Russian, Ukrainian)
+    EastSlavic,
+    /// `one` is 1, `few` ends with 2-4 but not 12-14, `many` is everything else
+    Polish,
+    /// `one` is 1, `few` is 2-4, `other` is everything else (Czech and Slovak)
+    CzechSlovak,
+}
+
+impl PluralRule {
+    fn for_language(language: &str) -> Self {
+        match language {
+            "ja" | "zh" | "ko" | "th" | "vi" | "id" | "ms" | "lo" | "my" | "km" | "yue" => {
+                Self::Single
+            }
+            "fr" | "pt" | "hy" | "kab" => Self::ZeroOneOther,
+            "ru" | "uk" | "be" | "sr" | "hr" | "bs" | "sh" => Self::EastSlavic,
+            "pl" => Self::Polish,
+            "cs" | "sk" => Self::CzechSlovak,
+            _ => Self::OneOther,
+        }
+    }
+
+    /// The index of the plural category of `n`. Fractional numbers use the last category.
+    fn category(self, n: f64) -> usize {
+        let last = match self {
+            Self::Single => 0,
+            Self::OneOther | Self::ZeroOneOther => 1,
+            Self::EastSlavic | Self::Polish | Self::CzechSlovak => 2,
+        };
+        if n.fract() != 0. {
+            return last;
+        }
+        let n = n.abs() as u64;
+        let (n10, n100) = (n % 10, n % 100);
+        let few = (2..=4).contains(&n10) && !(12..=14).contains(&n100);
+        match self {
+            Self::Single => 0,
+            Self::OneOther => usize::from(n != 1),
+            Self::ZeroOneOther => usize::from(n > 1),
+            Self::EastSlavic if n10 == 1 && n100 != 11 => 0,
+            Self::Polish | Self::CzechSlovak if n == 1 => 0,
+            Self::EastSlavic | Self::Polish if few => 1,
+            Self::CzechSlovak if (2..=4).contains(&n) => 1,
+            _ => last,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers() {
+        let de: Locale = "de_DE".parse().unwrap();
+        assert_eq!(de.localize_number("1234567.25"), "1.234.567,25");
+        assert_eq!(de.localize_number(" -123.4"), " -123,4");
+        assert_eq!(Locale::default().localize_number("1234.5"), "1234.5");
+        assert!("xx_YY".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn plurals() {
+        let forms = |locale: &str, n: f64| {
+            let forms = ["a".into(), "b".into(), "c".into()];
+            locale
+                .parse::<Locale>()
+                .unwrap()
+                .plural(n, &forms)
+                .to_string()
+        };
+        assert_eq!(forms("en_US", 1.), "a");
+        assert_eq!(forms("en_US", 0.), "b");
+        assert_eq!(forms("fr_FR", 0.), "a");
+        assert_eq!(forms("ru_RU", 21.), "a");
+        assert_eq!(forms("ru_RU", 23.), "b");
+        assert_eq!(forms("ru_RU", 12.), "c");
+        assert_eq!(forms("pl_PL", 22.), "b");
+        assert_eq!(forms("pl_PL", 21.), "c");
+        assert_eq!(forms("cs_CZ", 4.), "b");
+        assert_eq!(forms("ja_JP", 5.), "a");
+        let two = ["x".into(), "y".into()];
+        assert_eq!(Locale::default().plural(2., &two[..1]), "x");
+    }
+}
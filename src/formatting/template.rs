@@ -1,11 +1,15 @@
-use super::formatter::{new_formatter, Formatter};
-use super::{Fragment, Values};
+use super::formatter::{is_transformation, new_formatter_chain, Formatter};
+use super::value::{Value, ValueInner, ValueType};
+use super::{Fragment, Placeholders, Values};
+use crate::click::SLIDER_INSTANCE;
 use crate::config::SharedConfig;
 use crate::errors::*;
 
 use std::iter::Peekable;
 use std::str::FromStr;
 
+use unicode_width::UnicodeWidthStr;
+
 #[derive(Debug)]
 pub struct FormatTemplate(pub Vec<TokenList>);
 
@@ -23,6 +27,13 @@ pub enum Token {
     Icon {
         name: String,
     },
+    /// `$name{body}` or `$name{body}.join(separator)`. If the value is a list, `body` is rendered
+    /// once per item. Otherwise, this is the same as a placeholder followed by `{body}`.
+    Iterator {
+        name: String,
+        body: FormatTemplate,
+        separator: Option<String>,
+    },
 }
 
 impl FormatTemplate {
@@ -31,6 +42,7 @@ impl FormatTemplate {
             token_list.0.iter().any(|token| match token {
                 Token::Placeholder { name, .. } => name == key,
                 Token::Recursive(rec) => rec.contains_key(key),
+                Token::Iterator { name, body, .. } => name == key || body.contains_key(key),
                 _ => false,
             })
         })
@@ -48,11 +60,43 @@ impl FormatTemplate {
         Ok(Vec::new())
     }
 
+    /// Let the formatters know about the new values. The formatters in the body of a list are
+    /// shared by all items, so they are not updated and a `graph` there only shows the current
+    /// value of each item.
+    pub fn update(&self, values: &Values) {
+        for tl in &self.0 {
+            for t in &tl.0 {
+                match t {
+                    Token::Recursive(r) => r.update(values),
+                    Token::Iterator { name, body, .. } => {
+                        let is_list = values
+                            .get(name.as_str())
+                            .is_some_and(|value| matches!(value.inner, ValueInner::List(_)));
+                        if !is_list {
+                            body.update(values);
+                        }
+                    }
+                    Token::Placeholder {
+                        name,
+                        formatter: Some(f),
+                    } => {
+                        if let Some(value) = values.get(name.as_str()) {
+                            f.update(&value.inner);
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
     pub fn init_intervals(&self, intervals: &mut Vec<u64>) {
         for tl in &self.0 {
             for t in &tl.0 {
                 match t {
-                    Token::Recursive(r) => r.init_intervals(intervals),
+                    Token::Recursive(r) | Token::Iterator { body: r, .. } => {
+                        r.init_intervals(intervals);
+                    }
                     Token::Placeholder {
                         formatter: Some(f), ..
                     } => {
@@ -65,6 +109,74 @@ impl FormatTemplate {
             }
         }
     }
+
+    /// Check the placeholders used in this template against the ones provided by a block
+    pub fn validate(&self, placeholders: &Placeholders, warnings: &mut Vec<String>) {
+        for tl in &self.0 {
+            for t in &tl.0 {
+                match t {
+                    Token::Recursive(r) => r.validate(placeholders, warnings),
+                    Token::Placeholder { name, formatter } => {
+                        let ty = match placeholder_type(placeholders, name, warnings) {
+                            Some(ty) => ty,
+                            None => continue,
+                        };
+                        match formatter {
+                            Some(f) => {
+                                if let Err(formatter) = f.check_type(ty) {
+                                    warnings.push(format!(
+                                        "Placeholder '${name}' is a {ty} and cannot be formatted \
+                                         with '{formatter}'"
+                                    ));
+                                }
+                            }
+                            None if matches!(ty, ValueType::List(_)) => warnings.push(format!(
+                                "Placeholder '${name}' is a list and should be followed by a \
+                                 format for its items, e.g. '${name}{{...}}'"
+                            )),
+                            _ => (),
+                        }
+                    }
+                    Token::Iterator {
+                        name,
+                        body,
+                        separator,
+                    } => match placeholder_type(placeholders, name, warnings) {
+                        Some(ValueType::List(items)) => body.validate(items, warnings),
+                        Some(_) if separator.is_some() => {
+                            warnings.push(format!(
+                                "'join' can only be used with lists but '${name}' is not a list"
+                            ));
+                            body.validate(placeholders, warnings);
+                        }
+                        _ => body.validate(placeholders, warnings),
+                    },
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+/// Find the type of a placeholder, recording a warning if the block does not provide it
+fn placeholder_type(
+    placeholders: &Placeholders,
+    name: &str,
+    warnings: &mut Vec<String>,
+) -> Option<ValueType> {
+    let ty = placeholders.iter().find_map(|&(pattern, ty)| {
+        let matches = match pattern.strip_suffix("<N>") {
+            Some(prefix) => name
+                .strip_prefix(prefix)
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())),
+            None => pattern == name,
+        };
+        matches.then_some(ty)
+    });
+    if ty.is_none() {
+        warnings.push(format!("Unknown placeholder '${name}'"));
+    }
+    ty
 }
 
 impl TokenList {
@@ -91,25 +203,8 @@ impl TokenList {
                     cur = retval.pop().unwrap_or_default();
                 }
                 Token::Placeholder { name, formatter } => {
-                    let value = values
-                        .get(name.as_str())
-                        .or_format_error(|| format!("Placeholder '{}' not found", name))?;
-                    let formatter = formatter
-                        .as_ref()
-                        .map(Box::as_ref)
-                        .unwrap_or_else(|| value.default_formatter());
-                    let formatted = formatter.format(&value.inner)?;
-                    if value.metadata == cur.metadata {
-                        cur.text.push_str(&formatted);
-                    } else {
-                        if !cur.text.is_empty() {
-                            retval.push(cur);
-                        }
-                        cur = Fragment {
-                            text: formatted,
-                            metadata: value.metadata,
-                        };
-                    }
+                    let value = get_value(values, name)?;
+                    push_placeholder(&mut retval, &mut cur, value, formatter.as_deref(), config)?;
                 }
                 Token::Icon { name } => {
                     let icon = config
@@ -124,6 +219,33 @@ impl TokenList {
                         cur = icon.into();
                     }
                 }
+                Token::Iterator {
+                    name,
+                    body,
+                    separator,
+                } => {
+                    let value = get_value(values, name)?;
+                    let rendered = match &value.inner {
+                        ValueInner::List(items) => {
+                            render_list(items, value, body, separator.as_deref(), config)?
+                        }
+                        _ if separator.is_some() => {
+                            return Err(Error::new_format(format!(
+                                "'join' cannot be applied to '{}' because it is not a list",
+                                name
+                            )));
+                        }
+                        _ => {
+                            push_placeholder(&mut retval, &mut cur, value, None, config)?;
+                            body.render(values, config)?
+                        }
+                    };
+                    if !cur.text.is_empty() {
+                        retval.push(cur);
+                    }
+                    retval.extend(rendered);
+                    cur = retval.pop().unwrap_or_default();
+                }
             }
         }
 
@@ -135,21 +257,126 @@ impl TokenList {
     }
 }
 
+fn get_value<'a>(values: &'a Values, name: &str) -> Result<&'a Value> {
+    values
+        .get(name)
+        .or_format_error(|| format!("Placeholder '{}' not found", name))
+}
+
+fn push_placeholder(
+    retval: &mut Vec<Fragment>,
+    cur: &mut Fragment,
+    value: &Value,
+    formatter: Option<&dyn Formatter>,
+    config: &SharedConfig,
+) -> Result<()> {
+    let formatter = formatter.unwrap_or_else(|| value.default_formatter());
+    let formatted = formatter.format(&value.inner, config)?;
+    let mut metadata = value.metadata;
+    // Only the slider itself is clickable, not e.g. the number next to it
+    if metadata.instance == Some(SLIDER_INSTANCE) {
+        match formatter.slider_range() {
+            Some(range) => metadata.slider = Some(range),
+            None => metadata.instance = None,
+        }
+    }
+    if metadata == cur.metadata {
+        cur.text.push_str(&formatted);
+    } else {
+        let prev = std::mem::replace(
+            cur,
+            Fragment {
+                text: formatted,
+                metadata,
+            },
+        );
+        if !prev.text.is_empty() {
+            retval.push(prev);
+        }
+    }
+    Ok(())
+}
+
+fn render_list(
+    items: &[Values],
+    list: &Value,
+    body: &FormatTemplate,
+    separator: Option<&str>,
+    config: &SharedConfig,
+) -> Result<Vec<Fragment>> {
+    let mut retval: Vec<Fragment> = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        let mut fragments = body.render(item, config)?;
+        if let Some(instance) = list.metadata.instance {
+            for fragment in &mut fragments {
+                fragment.metadata.instance.get_or_insert(instance + i);
+            }
+        }
+        if i != 0 {
+            if let Some(separator) = separator.filter(|s| !s.is_empty()) {
+                fragments.insert(0, separator.to_string().into());
+            }
+        }
+        // Merge adjacent fragments with the same metadata to keep the number of i3bar blocks low
+        for fragment in fragments {
+            match retval.last_mut() {
+                Some(last) if last.metadata == fragment.metadata => {
+                    last.text.push_str(&fragment.text);
+                }
+                _ => retval.push(fragment),
+            }
+        }
+    }
+    Ok(retval)
+}
+
 impl FromStr for FormatTemplate {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
         let mut it = s.chars().chain(std::iter::once('}')).peekable();
-        let template = read_format_template(&mut it)?;
-        if it.next().is_some() {
-            Err(Error::new("Unexpected '}'"))
-        } else {
-            Ok(template)
+        match read_format_template(&mut it) {
+            // The top-level template has been closed by an unmatched '}' which was just consumed
+            Ok(_) if it.peek().is_some() => Err(with_position(
+                Error::new("Unexpected '}'"),
+                s,
+                it.count() + 1,
+            )),
+            Ok(template) => Ok(template),
+            // The iterator is left at the position where the parser gave up
+            Err(e) => Err(with_position(e, s, it.count())),
         }
     }
 }
 
-fn read_format_template(it: &mut Peekable<impl Iterator<Item = char>>) -> Result<FormatTemplate> {
+/// Append the column at which parsing failed to the error message, followed by the format
+/// string and a caret pointing at that column, e.g.
+///
+/// ```text
+/// Missing ')' at column 5
+///   $cpu.eng(2
+///        ^
+/// ```
+fn with_position(mut error: Error, s: &str, remaining: usize) -> Error {
+    // `remaining` also counts the implicit closing '}'
+    let total = s.chars().count();
+    let pos = (total + 1).saturating_sub(remaining).min(total);
+    let prefix: String = s.chars().take(pos).collect();
+    let message = error.message.as_deref().unwrap_or("Invalid format");
+    error.message = Some(
+        format!(
+            "{message} at column {}\n  {s}\n  {}^",
+            pos + 1,
+            " ".repeat(prefix.width())
+        )
+        .into(),
+    );
+    error
+}
+
+fn read_format_template(
+    it: &mut Peekable<impl Iterator<Item = char> + Clone>,
+) -> Result<FormatTemplate> {
     let mut token_lists = Vec::new();
     let mut cur_list = Vec::new();
     loop {
@@ -171,14 +398,38 @@ fn read_format_template(it: &mut Peekable<impl Iterator<Item = char>>) -> Result
             '$' => {
                 let _ = it.next();
                 let name = read_ident(it);
-                let formatter = match it.peek() {
+                match it.peek() {
                     Some('.') => {
                         let _ = it.next();
-                        Some(new_formatter(&read_formatter(it)?, &read_args(it)?)?)
+                        let start = it.clone();
+                        let formatter = match new_formatter_chain(&read_formatter_chain(it)?) {
+                            Ok(formatter) => formatter,
+                            Err(e) => {
+                                // Point at the formatter, not at the end of its arguments
+                                *it = start;
+                                return Err(e);
+                            }
+                        };
+                        cur_list.push(Token::Placeholder {
+                            name,
+                            formatter: Some(formatter),
+                        });
+                    }
+                    Some('{') => {
+                        let _ = it.next();
+                        let body = read_format_template(it)?;
+                        let separator = read_join(it)?;
+                        cur_list.push(Token::Iterator {
+                            name,
+                            body,
+                            separator,
+                        });
                     }
-                    _ => None,
-                };
-                cur_list.push(Token::Placeholder { name, formatter });
+                    _ => cur_list.push(Token::Placeholder {
+                        name,
+                        formatter: None,
+                    }),
+                }
             }
             '^' => {
                 let _ = it.next();
@@ -234,6 +485,55 @@ fn read_ident(it: &mut Peekable<impl Iterator<Item = char>>) -> String {
     retval
 }
 
+/// Read an optional `.join(<separator>)` which may follow `$list{...}`
+fn read_join(it: &mut Peekable<impl Iterator<Item = char> + Clone>) -> Result<Option<String>> {
+    let mut lookahead = it.clone();
+    if !consume_exact(&mut lookahead, ".join(") {
+        return Ok(None);
+    }
+    *it = lookahead;
+    let mut args = read_args(it)?;
+    match args.len() {
+        0 => Ok(Some(String::new())),
+        1 => Ok(args.pop()),
+        _ => Err(Error::new("'join' takes at most one argument")),
+    }
+}
+
+/// Read `<formatter>(<args>)`, optionally followed by `.<formatter>(<args>)` if the previous
+/// formatter is a transformation (e.g. `re(a,b).str(0,10)`)
+fn read_formatter_chain(
+    it: &mut Peekable<impl Iterator<Item = char> + Clone>,
+) -> Result<Vec<(String, Vec<String>)>> {
+    let mut chain = Vec::new();
+    loop {
+        let name = read_formatter(it)?;
+        let args = read_args(it)?;
+        let can_continue = is_transformation(&name);
+        chain.push((name, args));
+        if !can_continue {
+            return Ok(chain);
+        }
+        // Check if it is followed by something like `.name(`
+        let mut lookahead = it.clone();
+        if lookahead.next() != Some('.') {
+            return Ok(chain);
+        }
+        let mut name_len = 0;
+        let followed_by_formatter = loop {
+            match lookahead.next() {
+                Some(c) if c.is_alphanumeric() || c == '_' || c == '-' => name_len += 1,
+                Some('(') => break name_len > 0,
+                _ => break false,
+            }
+        };
+        if !followed_by_formatter {
+            return Ok(chain);
+        }
+        let _ = it.next();
+    }
+}
+
 fn read_formatter(it: &mut impl Iterator<Item = char>) -> Result<String> {
     let mut retval = String::new();
     for c in it {
@@ -249,6 +549,7 @@ fn read_args(it: &mut impl Iterator<Item = char>) -> Result<Vec<String>> {
     let mut args = Vec::new();
     let mut cur_arg = String::new();
     let mut escaped = false;
+    let mut quoted = false;
     for c in it {
         if escaped {
             escaped = false;
@@ -257,6 +558,13 @@ fn read_args(it: &mut impl Iterator<Item = char>) -> Result<Vec<String>> {
         }
         match c {
             '\\' => escaped = true,
+            // Quoted arguments may contain ',' and ')' without escaping
+            '"' if quoted => quoted = false,
+            '"' if cur_arg.trim().is_empty() => {
+                cur_arg.clear();
+                quoted = true;
+            }
+            x if quoted => cur_arg.push(x),
             ',' => {
                 args.push(cur_arg);
                 cur_arg = String::new();
@@ -270,7 +578,11 @@ fn read_args(it: &mut impl Iterator<Item = char>) -> Result<Vec<String>> {
             x => cur_arg.push(x),
         }
     }
-    Err(Error::new("Missing ')'"))
+    if quoted {
+        Err(Error::new("Missing '\"'"))
+    } else {
+        Err(Error::new("Missing ')'"))
+    }
 }
 
 fn consume_exact(it: &mut impl Iterator<Item = char>, tag: &str) -> bool {
@@ -281,3 +593,173 @@ fn consume_exact(it: &mut impl Iterator<Item = char>, tag: &str) -> bool {
     }
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(format: &str, values: &Values) -> Result<String> {
+        let template: FormatTemplate = format.parse()?;
+        Ok(template
+            .render(values, &SharedConfig::default())?
+            .into_iter()
+            .map(|f| f.text)
+            .collect())
+    }
+
+    #[test]
+    fn list_iteration() {
+        let values = map! {
+            "list" => Value::list(vec![
+                map!("x" => Value::text("a".into())),
+                map!("x" => Value::text("b".into())),
+            ]),
+        };
+        assert_eq!(render("$list{<$x>}", &values).unwrap(), "<a><b>");
+        assert_eq!(render("$list{$x}.join(\", \")", &values).unwrap(), "a, b");
+        assert_eq!(render("$list{$x}.join(-)!", &values).unwrap(), "a-b!");
+        assert_eq!(render("$list{$y}|none", &values).unwrap(), "none");
+    }
+
+    #[test]
+    fn list_instances() {
+        let values = map! {
+            "list" => Value::list(vec![
+                map!("x" => Value::text("a".into())),
+                map!("x" => Value::text("b".into())),
+            ]).with_instance(10),
+        };
+        let template: FormatTemplate = "$list{$x}.join(-)".parse().unwrap();
+        let fragments = template.render(&values, &SharedConfig::default()).unwrap();
+        let instances: Vec<_> = fragments.iter().map(|f| f.metadata.instance).collect();
+        // Each item has its own instance, the separator has none
+        assert_eq!(instances, [Some(10), None, Some(11)]);
+    }
+
+    #[test]
+    fn graph_in_list() {
+        let values = map! {
+            "x" => Value::number(100),
+            "list" => Value::list(vec![
+                map!("x" => Value::number(0)),
+                map!("x" => Value::number(1)),
+            ]),
+        };
+        let template: FormatTemplate = "$list{$x.graph(1, 0, 1)}".parse().unwrap();
+        template.update(&values);
+        template.update(&values);
+        let fragments = template.render(&values, &SharedConfig::default()).unwrap();
+        let text: String = fragments.into_iter().map(|f| f.text).collect();
+        assert_eq!(text, "\u{2581}\u{2588}");
+    }
+
+    #[test]
+    fn slider_instances() {
+        let values = map!("vol" => Value::percents(50).with_slider());
+        let template: FormatTemplate = "$vol $vol.bar(2)".parse().unwrap();
+        let fragments = template.render(&values, &SharedConfig::default()).unwrap();
+        let instances: Vec<_> = fragments.iter().map(|f| f.metadata.instance).collect();
+        assert_eq!(instances, [None, Some(SLIDER_INSTANCE)]);
+        assert_eq!(fragments[1].metadata.slider, Some((0., 100.)));
+    }
+
+    #[test]
+    fn formatter_chain() {
+        let values = map! {
+            "title" => Value::text("Page — Mozilla Firefox".into()),
+        };
+        assert_eq!(
+            render("$title.re(\" — Mozilla Firefox$\", \"\").upper().", &values).unwrap(),
+            "PAGE."
+        );
+        assert_eq!(
+            render("$title.re(\" — .*\").str(0,3, left, \"\")", &values).unwrap(),
+            "Pag"
+        );
+        assert_eq!(
+            render("$title.str(0,4).upper()", &values).unwrap(),
+            "Page.upper()"
+        );
+    }
+
+    #[test]
+    fn map_formatter() {
+        let format = "$layout.map(\"English (US)=us\", \"Bulgarian (new phonetic)=bg\", ??)";
+        let values = map!("layout" => Value::text("English (US)".into()));
+        assert_eq!(render(format, &values).unwrap(), "us");
+        let values = map!("layout" => Value::text("German".into()));
+        assert_eq!(render(format, &values).unwrap(), "??");
+        assert_eq!(
+            render("$layout.map(English (US\\)=us, \"none\")", &values).unwrap(),
+            "none"
+        );
+    }
+
+    #[test]
+    fn plural_formatter() {
+        let format = "$count.eng(1) $count.plural(update, updates)";
+        let values = map!("count" => Value::number(1));
+        assert_eq!(render(format, &values).unwrap(), "1 update");
+        let values = map!("count" => Value::number(2));
+        assert_eq!(render(format, &values).unwrap(), "2 updates");
+    }
+
+    #[test]
+    fn flags_with_body() {
+        let values = map! {
+            "a" => Value::flag(),
+        };
+        assert_eq!(render("$a{set}|unset", &values).unwrap(), "set");
+        assert_eq!(render("$b{set}|unset", &values).unwrap(), "unset");
+        assert!(render("$a{set}.join(,)", &values).is_err());
+    }
+
+    #[test]
+    fn parse_error_position() {
+        let err = "$a.eng(2".parse::<FormatTemplate>().unwrap_err();
+        assert_eq!(
+            err.message.as_deref(),
+            Some("Missing ')' at column 9\n  $a.eng(2\n          ^")
+        );
+        let err = "$a.foo()".parse::<FormatTemplate>().unwrap_err();
+        assert!(err
+            .message
+            .unwrap()
+            .ends_with("at column 4\n  $a.foo()\n     ^"));
+        let err = "ab}c".parse::<FormatTemplate>().unwrap_err();
+        assert!(err.message.unwrap().ends_with("at column 3\n  ab}c\n    ^"));
+    }
+
+    #[test]
+    fn validation() {
+        const ITEMS: &Placeholders = &[("x", ValueType::Text)];
+        const PLACEHOLDERS: &Placeholders = &[
+            ("text", ValueType::Text),
+            ("num", ValueType::Number),
+            ("num<N>", ValueType::Number),
+            ("flag", ValueType::Flag),
+            ("list", ValueType::List(ITEMS)),
+        ];
+        let validate = |format: &str| {
+            let mut warnings = Vec::new();
+            format
+                .parse::<FormatTemplate>()
+                .unwrap()
+                .validate(PLACEHOLDERS, &mut warnings);
+            warnings
+        };
+        assert!(validate("$text.str(1) $num.eng() $num2 $flag{a}|$list{$x}.join(-)").is_empty());
+        assert_eq!(validate("$nums"), ["Unknown placeholder '$nums'"]);
+        assert_eq!(validate("$num{$x}"), ["Unknown placeholder '$x'"]);
+        assert_eq!(
+            validate("$text.eng()"),
+            ["Placeholder '$text' is a text and cannot be formatted with 'eng'"]
+        );
+        assert_eq!(
+            validate("$text.upper().bar()"),
+            ["Placeholder '$text' is a text and cannot be formatted with 'bar'"]
+        );
+        assert_eq!(validate("$flag{a}.join(-)").len(), 1);
+        assert_eq!(validate("$list").len(), 1);
+    }
+}
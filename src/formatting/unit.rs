@@ -10,12 +10,28 @@ pub enum Unit {
     Bytes,
     /// `b`
     Bits,
+    /// `B/s`
+    BytesPerSecond,
+    /// `b/s`
+    BitsPerSecond,
     /// `%`
     Percents,
-    /// `deg`
+    /// `C`
+    Celsius,
+    /// `F`
+    Fahrenheit,
+    /// `K`
+    Kelvin,
+    /// `deg`, a temperature in whatever unit it has, shown with just `°`
     Degrees,
     /// `s`
     Seconds,
+    /// `min`
+    Minutes,
+    /// `h`
+    Hours,
+    /// `d`
+    Days,
     /// `W`
     Watts,
     /// `Hz`
@@ -29,9 +45,17 @@ impl fmt::Display for Unit {
         f.write_str(match self {
             Self::Bytes => "B",
             Self::Bits => "b",
+            Self::BytesPerSecond => "B/s",
+            Self::BitsPerSecond => "b/s",
             Self::Percents => "%",
+            Self::Celsius => "°C",
+            Self::Fahrenheit => "°F",
+            Self::Kelvin => "K",
             Self::Degrees => "°",
             Self::Seconds => "s",
+            Self::Minutes => "min",
+            Self::Hours => "h",
+            Self::Days => "d",
             Self::Watts => "W",
             Self::Hertz => "Hz",
             Self::None => "",
@@ -46,9 +70,17 @@ impl FromStr for Unit {
         match s {
             "B" => Ok(Unit::Bytes),
             "b" => Ok(Unit::Bits),
+            "B/s" => Ok(Unit::BytesPerSecond),
+            "b/s" => Ok(Unit::BitsPerSecond),
             "%" => Ok(Unit::Percents),
+            "C" => Ok(Unit::Celsius),
+            "F" => Ok(Unit::Fahrenheit),
+            "K" => Ok(Unit::Kelvin),
             "deg" => Ok(Unit::Degrees),
             "s" => Ok(Unit::Seconds),
+            "min" => Ok(Unit::Minutes),
+            "h" => Ok(Unit::Hours),
+            "d" => Ok(Unit::Days),
             "W" => Ok(Unit::Watts),
             "Hz" => Ok(Unit::Hertz),
             "" => Ok(Unit::None),
@@ -57,14 +89,65 @@ impl FromStr for Unit {
     }
 }
 
+/// A physical quantity. Units of the same quantity can be converted to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quantity {
+    Data,
+    DataRate,
+    Temperature,
+    Time,
+}
+
 impl Unit {
+    fn quantity(self) -> Option<Quantity> {
+        match self {
+            Self::Bytes | Self::Bits => Some(Quantity::Data),
+            Self::BytesPerSecond | Self::BitsPerSecond => Some(Quantity::DataRate),
+            Self::Celsius | Self::Fahrenheit | Self::Kelvin => Some(Quantity::Temperature),
+            Self::Seconds | Self::Minutes | Self::Hours | Self::Days => Some(Quantity::Time),
+            Self::Percents | Self::Degrees | Self::Watts | Self::Hertz | Self::None => None,
+        }
+    }
+
+    /// Convert to the base unit of the quantity (bits, bits per second, kelvins or seconds)
+    fn to_base(self, value: f64) -> f64 {
+        match self {
+            Self::Bytes | Self::BytesPerSecond => value * 8.,
+            Self::Celsius => value + 273.15,
+            Self::Fahrenheit => (value - 32.) / 1.8 + 273.15,
+            Self::Minutes => value * 60.,
+            Self::Hours => value * 3_600.,
+            Self::Days => value * 86_400.,
+            _ => value,
+        }
+    }
+
+    /// Convert from the base unit of the quantity, the inverse of `to_base`
+    #[allow(clippy::wrong_self_convention)]
+    fn from_base(self, value: f64) -> f64 {
+        match self {
+            Self::Bytes | Self::BytesPerSecond => value / 8.,
+            Self::Celsius => value - 273.15,
+            Self::Fahrenheit => (value - 273.15) * 1.8 + 32.,
+            Self::Minutes => value / 60.,
+            Self::Hours => value / 3_600.,
+            Self::Days => value / 86_400.,
+            _ => value,
+        }
+    }
+
     pub fn convert(self, value: f64, unit: Self) -> Result<f64> {
-        match (self, unit) {
-            (x, y) if x == y => Ok(value),
-            (Self::Bytes, Self::Bits) => Ok(value * 8.),
-            (Self::Bits, Self::Bytes) => Ok(value / 8.),
+        match (self.quantity(), unit.quantity()) {
+            _ if self == unit => Ok(value),
+            (Some(a), Some(b)) if a == b => Ok(unit.from_base(self.to_base(value))),
+            // Before rates had their own units, `B` and `b` were used for them (e.g.
+            // `$speed_down.eng(3,B,K)`), so they are accepted for rates as well
+            (Some(Quantity::DataRate), Some(Quantity::Data)) => {
+                Ok(unit.from_base(self.to_base(value)))
+            }
+            (Some(Quantity::Temperature), _) if unit == Self::Degrees => Ok(value),
             _ => Err(Error::new(format!(
-                "Failed to convert '{}' to '{}",
+                "Failed to convert '{}' to '{}'",
                 self, unit
             ))),
         }
@@ -72,9 +155,46 @@ impl Unit {
 
     pub fn clamp_prefix(self, prefix: Prefix) -> Prefix {
         match self {
-            Self::Bytes | Self::Bits => prefix.max(Prefix::One),
-            Self::Percents | Self::None => Prefix::One,
+            Self::Bytes | Self::Bits | Self::BytesPerSecond | Self::BitsPerSecond => {
+                prefix.max(Prefix::One)
+            }
+            Self::Percents
+            | Self::Celsius
+            | Self::Fahrenheit
+            | Self::Kelvin
+            | Self::Degrees
+            | Self::Minutes
+            | Self::Hours
+            | Self::Days
+            | Self::None => Prefix::One,
             _ => prefix,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(value: f64, from: &str, to: &str) -> f64 {
+        let from: Unit = from.parse().unwrap();
+        from.convert(value, to.parse().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(convert(2., "B", "b"), 16.);
+        assert_eq!(convert(16., "b/s", "B/s"), 2.);
+        assert!((convert(100., "C", "F") - 212.).abs() < 1e-9);
+        assert!((convert(32., "F", "K") - 273.15).abs() < 1e-9);
+        assert!((convert(0., "K", "C") + 273.15).abs() < 1e-9);
+        assert_eq!(convert(90., "min", "h"), 1.5);
+        assert_eq!(convert(2., "d", "s"), 172_800.);
+        assert!(Unit::Bytes.convert(1., Unit::BytesPerSecond).is_err());
+        assert_eq!(convert(2., "B/s", "b"), 16.);
+        assert_eq!(convert(2., "B/s", "B"), 2.);
+        assert_eq!(convert(50., "F", "deg"), 50.);
+        assert!(Unit::Seconds.convert(1., Unit::Degrees).is_err());
+        assert!(Unit::Seconds.convert(1., Unit::Celsius).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use super::formatter;
 use super::unit::Unit;
-use super::Metadata;
+use super::{Metadata, Placeholders, Values};
+use crate::click::SLIDER_INSTANCE;
 
 #[derive(Debug, Clone)]
 pub struct Value {
@@ -13,9 +14,33 @@ pub enum ValueInner {
     Text(String),
     Icon(String),
     Number { val: f64, unit: Unit },
+    List(Vec<Values>),
     Flag,
 }
 
+/// The type of a placeholder, as declared by a block. Used to validate format templates.
+#[derive(Debug, Clone, Copy)]
+pub enum ValueType {
+    Text,
+    Icon,
+    Number,
+    Flag,
+    /// A list whose items have the given placeholders
+    List(&'static Placeholders),
+}
+
+impl std::fmt::Display for ValueType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Text => "text",
+            Self::Icon => "icon",
+            Self::Number => "number",
+            Self::Flag => "flag",
+            Self::List(_) => "list",
+        })
+    }
+}
+
 pub trait IntoF64 {
     fn into_f64(self) -> f64;
 }
@@ -70,8 +95,14 @@ impl Value {
     pub fn percents(val: impl IntoF64) -> Self {
         Self::number_unit(val, Unit::Percents)
     }
-    pub fn degrees(val: impl IntoF64) -> Self {
-        Self::number_unit(val, Unit::Degrees)
+    pub fn bytes_per_second(val: impl IntoF64) -> Self {
+        Self::number_unit(val, Unit::BytesPerSecond)
+    }
+    pub fn bits_per_second(val: impl IntoF64) -> Self {
+        Self::number_unit(val, Unit::BitsPerSecond)
+    }
+    pub fn celsius(val: impl IntoF64) -> Self {
+        Self::number_unit(val, Unit::Celsius)
     }
     pub fn seconds(val: impl IntoF64) -> Self {
         Self::number_unit(val, Unit::Seconds)
@@ -85,6 +116,13 @@ impl Value {
     pub fn number(val: impl IntoF64) -> Self {
         Self::number_unit(val, Unit::None)
     }
+
+    /// A list of value maps. If the list has an instance set, `n`th item's fragments are assigned
+    /// `instance + n` (unless they already have an instance), so the block must not use the
+    /// instances `instance..instance + len` for anything else.
+    pub fn list(items: Vec<Values>) -> Self {
+        Self::new(ValueInner::List(items))
+    }
 }
 
 /// Set options
@@ -94,6 +132,13 @@ impl Value {
         self
     }
 
+    /// Mark the value as adjustable by clicking on it. When the value is rendered with a slider
+    /// formatter (`bar`), clicks on it are reported with `SLIDER_INSTANCE`, and
+    /// `I3BarEvent::slider_value` translates the click position into a new value.
+    pub fn with_slider(self) -> Self {
+        self.with_instance(SLIDER_INSTANCE)
+    }
+
     pub fn underline(mut self, val: bool) -> Self {
         self.metadata.underline = val;
         self
@@ -106,7 +151,9 @@ impl Value {
 
     pub fn default_formatter(&self) -> &'static dyn formatter::Formatter {
         match &self.inner {
-            ValueInner::Text(_) | ValueInner::Icon(_) => &formatter::DEFAULT_STRING_FORMATTER,
+            ValueInner::Text(_) | ValueInner::Icon(_) | ValueInner::List(_) => {
+                &formatter::DEFAULT_STRING_FORMATTER
+            }
             ValueInner::Number { .. } => &formatter::DEFAULT_NUMBER_FORMATTER,
             ValueInner::Flag => &formatter::DEFAULT_FLAG_FORMATTER,
         }
@@ -5,40 +5,72 @@ use std::collections::HashMap;
 
 #[derive(Deserialize, Debug, Clone)]
 #[serde(try_from = "IconsConfigRaw")]
-pub struct Icons(pub HashMap<String, String>);
+pub struct Icons(pub HashMap<String, Icon>);
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Icon {
+    Single(String),
+    /// Icons for increasing values, e.g. from an empty to a full battery
+    Family(Vec<String>),
+}
+
+impl From<&str> for Icon {
+    fn from(icon: &str) -> Self {
+        Self::Single(icon.into())
+    }
+}
+
+/// Icon families which replaced numbered icons, with the old icons from the lowest to the highest
+/// value. The old icons are still accepted in icon sets and in overrides.
+const LEGACY_FAMILIES: &[(&str, &[&str])] = &[
+    (
+        "backlight",
+        &[
+            "backlight_empty",
+            "backlight_1",
+            "backlight_2",
+            "backlight_3",
+            "backlight_4",
+            "backlight_5",
+            "backlight_6",
+            "backlight_7",
+            "backlight_8",
+            "backlight_9",
+            "backlight_10",
+            "backlight_11",
+            "backlight_12",
+            "backlight_13",
+            "backlight_full",
+        ],
+    ),
+    (
+        "bat",
+        &[
+            "bat_10", "bat_20", "bat_30", "bat_40", "bat_50", "bat_60", "bat_70", "bat_80",
+            "bat_90", "bat_full",
+        ],
+    ),
+    ("volume", &["volume_empty", "volume_half", "volume_full"]),
+    (
+        "microphone",
+        &["microphone_empty", "microphone_half", "microphone_full"],
+    ),
+];
 
 impl Default for Icons {
     fn default() -> Self {
         // "none" icon set
         Self(map! {
-            "backlight_empty" => "BRIGHT",
+            "backlight" => Icon::Family(vec!["BRIGHT".into()]),
             "backlight_full" => "BRIGHT",
-            "backlight_1" =>  "BRIGHT",
-            "backlight_2" =>  "BRIGHT",
-            "backlight_3" =>  "BRIGHT",
-            "backlight_4" =>  "BRIGHT",
-            "backlight_5" =>  "BRIGHT",
-            "backlight_6" =>  "BRIGHT",
-            "backlight_7" =>  "BRIGHT",
-            "backlight_8" =>  "BRIGHT",
-            "backlight_9" =>  "BRIGHT",
-            "backlight_10" => "BRIGHT",
-            "backlight_11" => "BRIGHT",
-            "backlight_12" => "BRIGHT",
-            "backlight_13" => "BRIGHT",
-            "bat_10" => "BAT",
-            "bat_20" => "BAT",
-            "bat_30" => "BAT",
-            "bat_40" => "BAT",
-            "bat_50" => "BAT",
-            "bat_60" => "BAT",
-            "bat_70" => "BAT",
-            "bat_80" => "BAT",
-            "bat_90" => "BAT",
+            // "FULL" above 90%, see `util::battery_level_fraction`
+            "bat" => Icon::Family(
+                vec!["BAT".into(); 9].into_iter().chain(["FULL".into()]).collect()
+            ),
             "bat_charging" => "CHG",
             "bat_discharging" => "DCG",
             "bat_empty" => "EMP",
-            "bat_full" => "FULL",
             "bat_not_available" => "BAT N/A",
             "bell" => "ON",
             "bell-slash" => "OFF",
@@ -83,6 +115,7 @@ impl Default for Icons {
             "pomodoro_stopped" => "STOPPED",
             "resolution" => "RES",
             "tasks" => "TSK",
+            "temp" => Icon::Family(vec!["TEMP".into()]),
             "thermometer" => "TEMP",
             "time" => "TIME",
             "toggle_off" => "OFF",
@@ -90,13 +123,9 @@ impl Default for Icons {
             "unknown" => "??",
             "update" => "UPD",
             "uptime" => "UP",
-            "volume_empty" => "VOL",
-            "volume_full" => "VOL",
-            "volume_half" => "VOL",
+            "volume" => Icon::Family(vec!["VOL".into()]),
             "volume_muted" => "VOL MUTED",
-            "microphone_empty" => "MIC ",
-            "microphone_full" => "MIC",
-            "microphone_half" => "MIC",
+            "microphone" => Icon::Family(vec!["MIC".into()]),
             "microphone_muted" => "MIC MUTED",
             "weather_clouds" => "CLOUDY",
             "weather_default" => "WEATHER",
@@ -116,20 +145,76 @@ impl Icons {
         } else {
             let file = util::find_file(file, Some("icons"), Some("toml"))
                 .or_error(|| format!("Icon set '{}' not found", file))?;
-            Ok(Icons(util::deserialize_toml_file(&file)?))
+            let mut icons = Icons(util::deserialize_toml_file(&file)?);
+            icons.apply_legacy_icons(&HashMap::new());
+            Ok(icons)
         }
     }
 
-    pub fn apply_overrides(&mut self, overrides: HashMap<String, String>) {
+    pub fn apply_overrides(&mut self, overrides: HashMap<String, Icon>) {
+        self.apply_legacy_icons(&overrides);
         self.0.extend(overrides);
     }
+
+    /// Build the families which are missing from the old numbered icons, and replace the icons of
+    /// a family which are overridden with the old names (e.g. `bat_50`)
+    fn apply_legacy_icons(&mut self, overrides: &HashMap<String, Icon>) {
+        for &(family, names) in LEGACY_FAMILIES {
+            if overrides.contains_key(family) {
+                continue;
+            }
+            let single = |name: &str| match overrides.get(name).or_else(|| self.0.get(name)) {
+                Some(Icon::Single(icon)) => Some(icon.clone()),
+                _ => None,
+            };
+            let icons: Option<Vec<String>> = if self.0.contains_key(family) {
+                if !names.iter().any(|name| overrides.contains_key(*name)) {
+                    continue;
+                }
+                let len = names.len() as f64;
+                names
+                    .iter()
+                    .enumerate()
+                    .map(|(i, name)| match overrides.get(*name) {
+                        Some(Icon::Single(icon)) => Some(icon.clone()),
+                        _ => self.get_in_family(family, i as f64 / len).map(Into::into),
+                    })
+                    .collect()
+            } else {
+                names.iter().map(|name| single(name)).collect()
+            };
+            if let Some(icons) = icons {
+                self.0.insert(family.into(), Icon::Family(icons));
+            }
+        }
+    }
+
+    pub fn get(&self, icon: &str) -> Option<&str> {
+        match self.0.get(icon)? {
+            Icon::Single(icon) => Some(icon),
+            Icon::Family(_) => None,
+        }
+    }
+
+    /// Pick an icon of a family by `fraction`, which ranges from `0` (the first icon) to `1` (the
+    /// last icon). The range is split evenly between the icons. A single icon is a family of one.
+    pub fn get_in_family(&self, family: &str, fraction: f64) -> Option<&str> {
+        match self.0.get(family)? {
+            Icon::Single(icon) => Some(icon),
+            Icon::Family(icons) => {
+                let last = icons.len().checked_sub(1)?;
+                let index = (fraction.clamp(0., 1.) * icons.len() as f64) as usize;
+                Some(&icons[index.min(last)])
+            }
+        }
+    }
 }
 
 #[derive(Deserialize, Default)]
 #[serde(deny_unknown_fields, default)]
 struct IconsConfigRaw {
     icons: Option<String>,
-    overrides: Option<HashMap<String, String>>,
+    overrides: Option<HashMap<String, Icon>>,
 }
 
 impl TryFrom<IconsConfigRaw> for Icons {
@@ -138,10 +223,77 @@ impl TryFrom<IconsConfigRaw> for Icons {
     fn try_from(raw: IconsConfigRaw) -> Result<Self, Self::Error> {
         let mut icons = Self::from_file(raw.icons.as_deref().unwrap_or("none"))?;
         if let Some(overrides) = raw.overrides {
-            for icon in overrides {
-                icons.0.insert(icon.0, icon.1);
-            }
+            icons.apply_overrides(overrides);
         }
         Ok(icons)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::battery_level_fraction;
+
+    #[test]
+    fn families() {
+        let icons = Icons(map! {
+            "vol" => Icon::Family(vec!["low".into(), "mid".into(), "high".into()]),
+            "mute" => "muted",
+        });
+        assert_eq!(icons.get_in_family("vol", 0.), Some("low"));
+        assert_eq!(icons.get_in_family("vol", 0.5), Some("mid"));
+        assert_eq!(icons.get_in_family("vol", 0.7), Some("high"));
+        assert_eq!(icons.get_in_family("vol", 1.), Some("high"));
+        assert_eq!(icons.get_in_family("vol", 7.), Some("high"));
+        assert_eq!(icons.get_in_family("mute", 0.7), Some("muted"));
+        assert_eq!(icons.get_in_family("missing", 0.), None);
+        assert_eq!(icons.get("mute"), Some("muted"));
+        assert_eq!(icons.get("vol"), None);
+    }
+
+    #[test]
+    fn legacy_icons() {
+        let mut icons = Icons(map! {
+            "volume_empty" => "low",
+            "volume_half" => "mid",
+            "volume_full" => "high",
+            "bat" => Icon::Family(vec!["empty".into(), "full".into()]),
+        });
+        icons.apply_legacy_icons(&HashMap::new());
+        assert_eq!(icons.get_in_family("volume", 0.5), Some("mid"));
+
+        icons.apply_overrides(map! {
+            "bat_50" => "half",
+            "volume_full" => "max",
+        });
+        assert_eq!(icons.get_in_family("bat", 0.), Some("empty"));
+        assert_eq!(icons.get_in_family("bat", 0.45), Some("half"));
+        assert_eq!(icons.get_in_family("bat", 0.55), Some("full"));
+        assert_eq!(icons.get_in_family("volume", 1.), Some("max"));
+    }
+
+    #[test]
+    fn battery_levels() {
+        let bat = |icons: &Icons, level| {
+            icons
+                .get_in_family("bat", battery_level_fraction(level))
+                .unwrap()
+                .to_string()
+        };
+
+        let none = Icons::default();
+        assert_eq!(bat(&none, 0.), "BAT");
+        assert_eq!(bat(&none, 50.), "BAT");
+        assert_eq!(bat(&none, 90.), "BAT");
+        assert_eq!(bat(&none, 91.), "FULL");
+
+        let icons = Icons(map! {
+            "bat" => Icon::Family((1..=10).map(|i| (i * 10).to_string()).collect()),
+        });
+        assert_eq!(bat(&icons, 0.), "10");
+        assert_eq!(bat(&icons, 10.), "10");
+        assert_eq!(bat(&icons, 11.), "20");
+        assert_eq!(bat(&icons, 90.), "90");
+        assert_eq!(bat(&icons, 100.), "100");
+    }
+}
@@ -27,6 +27,7 @@ use futures::stream::{AbortHandle, Stream, StreamExt};
 use once_cell::sync::Lazy;
 use protocol::i3bar_block::I3BarBlock;
 use protocol::i3bar_event::I3BarEvent;
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -35,13 +36,18 @@ use tokio::process::Command;
 use tokio::sync::mpsc;
 
 use blocks::{BlockEvent, BlockFuture, BlockType, CommonApi, CommonConfig};
-use click::ClickHandler;
+use click::{ClickHandler, MouseButton, PostActions, SLIDER_INSTANCE};
 use config::Config;
 use config::SharedConfig;
 use errors::*;
+use escape::CollectEscaped;
+use formatting::config::Config as FormatConfig;
 use formatting::{scheduling, Format};
 use protocol::i3bar_event::events_stream;
+use protocol::BlockOutput;
+use serde::Deserialize;
 use signals::{signals_stream, Signal};
+use themes::ThemeSet;
 use widget::{State, Widget};
 
 pub type BoxedFuture<T> = Pin<Box<dyn Future<Output = T>>>;
@@ -91,7 +97,10 @@ fn main() {
         .block_on(async move {
             let config_path = util::find_file(&args.config, None, Some("toml"))
                 .or_error(|| format!("Configuration file '{}' not found", args.config))?;
-            let mut config: Config = util::deserialize_toml_file(&config_path).config_error()?;
+            let mut config: toml::Value =
+                util::deserialize_toml_file(&config_path).config_error()?;
+            config::vars::interpolate(&mut config).config_error()?;
+            let mut config: Config = config.try_into().config_error()?;
             let blocks = std::mem::take(&mut config.blocks);
             let mut bar = BarState::new(config);
             for block_config in blocks {
@@ -103,7 +112,7 @@ fn main() {
         let error_widget = Widget::new_error(0, Default::default(), &error);
         println!(
             "{},",
-            serde_json::to_string(&error_widget.get_data().unwrap()).unwrap()
+            serde_json::to_string(&error_widget.get_data(&Default::default()).unwrap()).unwrap()
         );
         eprintln!("\n\n{}\n\n", error);
         dbg!(error);
@@ -128,10 +137,33 @@ pub struct Block {
     click_handler: ClickHandler,
     signal: Option<i32>,
     shared_config: SharedConfig,
+    theme_overrides: Option<HashMap<String, String>>,
+    command_timeout: Duration,
 
     error_format: Format,
     error_fullscreen_format: Format,
 
+    /// The main format last set by the block itself
+    format: Option<Format>,
+    /// The format set by `toggle_format_alt` or `cycle_format` click actions, which replaces the
+    /// block's main format (but not the formats for particular states, like `missing_format`)
+    format_override: Option<Format>,
+    format_alt: Option<Format>,
+    /// The format used while the block is expanded by the `expand` click action
+    format_expanded: Option<Format>,
+    expanded: bool,
+    /// Position in the list of `cycle_format`, zero being the block's format
+    format_cycle: usize,
+    /// Alternating tints are applied within groups of neighbouring blocks
+    group: Option<String>,
+    /// The output at the time the block was hidden by a click. Shown again once it changes.
+    hidden: Option<Vec<I3BarBlock>>,
+    /// A message which temporarily replaces the block's widget, and the ID used to remove it
+    flash: Option<(u64, Widget)>,
+    flash_id: u64,
+    /// The button awaiting confirmation, and the ID of the flash message with the prompt
+    confirming: Option<(u64, MouseButton)>,
+
     state: BlockState,
 }
 
@@ -152,6 +184,130 @@ impl Block {
             .send((self.id, widget.intervals()));
     }
 
+    /// Perform the actions of a click which are implemented by the bar rather than the block.
+    /// Returns `true` if the block has to be expanded or collapsed.
+    fn run_builtin_actions(
+        &mut self,
+        actions: &PostActions,
+        dark: bool,
+        flash_target: &click::FlashTarget,
+    ) -> Result<bool> {
+        let widget = match &self.state {
+            BlockState::Normal { widget } => widget,
+            _ => return Ok(false),
+        };
+
+        if let Some(format) = &actions.copy_to_clipboard {
+            // Copy off the main loop, so that a stuck clipboard tool does not freeze the bar
+            let text = widget.render_with(format)?;
+            let target = flash_target.clone();
+            tokio::spawn(async move {
+                if let Err(error) = subprocess::copy_to_clipboard(&text, target.timeout).await {
+                    let text = format!("Failed to copy to clipboard: {error}");
+                    target.flash(text, State::Critical).await;
+                }
+            });
+        }
+
+        if actions.toggle_format_alt {
+            self.format_override = match self.format_override {
+                Some(_) => None,
+                None => self.format_alt.clone(),
+            };
+            self.format_cycle = 0;
+        } else if let Some(formats) = &actions.cycle_format {
+            self.format_cycle = (self.format_cycle + 1) % (formats.len() + 1);
+            self.format_override = self.format_cycle.checked_sub(1).map(|i| formats[i].clone());
+        }
+        if actions.toggle_format_alt || actions.cycle_format.is_some() {
+            self.apply_format();
+        }
+
+        if actions.hide {
+            if let BlockState::Normal { widget } = &self.state {
+                self.hidden = Some(widget.get_data(self.shared_config.theme.get(dark))?);
+            }
+        }
+
+        Ok(actions.expand)
+    }
+
+    /// The format which replaces the block's main format, if any
+    fn format_replacement(&self) -> Option<&Format> {
+        match &self.format_expanded {
+            Some(format) if self.expanded => Some(format),
+            _ => self.format_override.as_ref(),
+        }
+    }
+
+    /// Switch to or from `format_expanded`
+    fn set_expanded(&mut self, expanded: bool) {
+        self.expanded = expanded;
+        self.apply_format();
+        self.notify_intervals();
+    }
+
+    /// Display the widget with the format replacement, or the block's own main format. Formats
+    /// for particular states are left alone.
+    fn apply_format(&mut self) {
+        let format = self.format_replacement().or(self.format.as_ref()).cloned();
+        if let (BlockState::Normal { widget }, Some(format)) = (&mut self.state, format) {
+            if widget.has_main_format() {
+                widget.set_format(format);
+            }
+        }
+    }
+
+    /// Show `text` instead of the block's widget for `duration`
+    fn flash(
+        &mut self,
+        text: &str,
+        duration: Duration,
+        state: State,
+        request_sender: &mpsc::Sender<Request>,
+    ) {
+        self.flash_id += 1;
+        let widget = Widget::new(self.id, self.shared_config.clone())
+            .with_text(format!(" {} ", text.chars().collect_pango::<String>()))
+            .with_state(state);
+        self.flash = Some((self.flash_id, widget));
+
+        let request = Request {
+            block_id: self.id,
+            cmd: RequestCmd::EndFlash(self.flash_id),
+        };
+        let request_sender = request_sender.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(duration).await;
+            let _ = request_sender.send(request).await;
+        });
+    }
+
+    /// Ask for confirmation of clicks on `[[block.click]]` entries with `confirm`. The click is
+    /// handled once the same button is clicked again while the prompt is shown, any other button
+    /// cancels it. Returns `true` if the click should be handled.
+    fn confirm_click(
+        &mut self,
+        event: &I3BarEvent,
+        request_sender: &mpsc::Sender<Request>,
+    ) -> bool {
+        let flash_id = self.flash.as_ref().map(|(id, _)| *id);
+        match self.confirming.take() {
+            Some((id, button)) if Some(id) == flash_id => {
+                self.flash = None;
+                button == event.button
+            }
+            _ => match self.click_handler.confirm_msg(event).map(str::to_string) {
+                Some(msg) => {
+                    self.flash(&msg, click::CONFIRM_TIMEOUT, State::Warning, request_sender);
+                    self.confirming = Some((self.flash_id, event.button));
+                    false
+                }
+                None => true,
+            },
+        }
+    }
+
     fn set_error(&mut self, fullscreen: bool, error: Error) {
         let mut widget = Widget::new(self.id, self.shared_config.clone())
             .with_state(State::Critical)
@@ -185,6 +341,13 @@ pub enum RequestCmd {
     SetWidget(Widget),
     UnsetWidget,
     SetError(Error),
+    Flash {
+        text: String,
+        duration: Duration,
+        state: State,
+    },
+    /// Remove the flash message with this ID, unless it has been replaced by a newer one
+    EndFlash(u64),
 }
 
 struct BarState {
@@ -203,6 +366,11 @@ struct BarState {
 
     signals_stream: BoxedStream<Signal>,
     events_stream: BoxedStream<I3BarEvent>,
+    dark_mode_stream: BoxedStream<bool>,
+    palette_updates_stream: BoxedStream<()>,
+    /// Whether the dark themes are used. Follows the desktop's color scheme, and can be toggled
+    /// with `dark_mode_signal`.
+    dark: bool,
 }
 
 impl BarState {
@@ -226,12 +394,29 @@ impl BarState {
                 config.invert_scrolling,
                 Duration::from_millis(config.double_click_delay),
             ),
+            dark_mode_stream: if config.shared.theme.dark.is_some() {
+                Box::pin(themes::color_scheme::dark_mode_stream())
+            } else {
+                Box::pin(futures::stream::pending())
+            },
+            palette_updates_stream: match &config.shared.theme.watched_palette {
+                Some(file) => match themes::import::file_updates(file) {
+                    Ok(updates) => Box::pin(updates),
+                    Err(error) => {
+                        eprintln!("Warning: cannot watch the theme palette: {error}");
+                        Box::pin(futures::stream::pending())
+                    }
+                },
+                None => Box::pin(futures::stream::pending()),
+            },
+            dark: false,
 
             config,
         }
     }
 
     async fn spawn_block(&mut self, mut block_config: toml::Value) -> Result<()> {
+        config::templates::apply(&mut block_config, &self.config.templates)?;
         let common_config = CommonConfig::new(&mut block_config)?;
         if let Some(cmd) = &common_config.if_command {
             if !Command::new("sh")
@@ -253,12 +438,18 @@ impl BarState {
         if let Some(icons_format) = common_config.icons_format {
             shared_config.icons_format = Arc::new(icons_format);
         }
-        if let Some(theme_overrides) = common_config.theme_overrides {
-            Arc::make_mut(&mut shared_config.theme).apply_overrides(&theme_overrides)?;
-        }
+        let theme_overrides = common_config.theme_overrides;
+        shared_config.theme = Arc::new(block_theme(
+            &self.config.shared.theme,
+            block_type,
+            theme_overrides.as_ref(),
+        )?);
         if let Some(icons_overrides) = common_config.icons_overrides {
             Arc::make_mut(&mut shared_config.icons).apply_overrides(icons_overrides);
         }
+        if let Some(locale) = common_config.locale {
+            shared_config.locale = Some(locale);
+        }
 
         let (event_sender, event_receiver) = mpsc::channel(64);
 
@@ -270,6 +461,7 @@ impl BarState {
             request_sender: self.request_sender.clone(),
 
             error_interval: Duration::from_secs(common_config.error_interval),
+            command_timeout: Duration::from_secs(common_config.command_timeout),
         };
 
         let error_format = common_config
@@ -279,6 +471,35 @@ impl BarState {
             .error_fullscreen_format
             .with_default(&self.config.error_fullscreen_format)?;
 
+        let format_alt = match common_config.format_alt {
+            Some(format_alt) => Some(format_alt.with_default("")?),
+            None => None,
+        };
+        let format_expanded = match common_config.format_expanded {
+            Some(format_expanded) => Some(format_expanded.with_default("")?),
+            None => None,
+        };
+        let mut click_handler = common_config.click;
+        if format_alt.is_some() {
+            click_handler.toggle_format_alt_on_left_click();
+        }
+
+        // Check every configured format against the placeholders the block provides
+        let formats = configured_formats(&block_config)
+            .into_iter()
+            .chain(format_alt.iter().map(|f| ("format_alt".into(), f.clone())))
+            .chain(
+                format_expanded
+                    .iter()
+                    .map(|f| ("format_expanded".into(), f.clone())),
+            )
+            .chain(click_handler.formats().map(|f| ("click".into(), f.clone())));
+        for (key, format) in formats {
+            for warning in format.validate(block_type.placeholders()) {
+                eprintln!("Warning: block '{block_type:?}': '{key}': {warning}");
+            }
+        }
+
         let (block_fut, abort_handle) = abortable(block_type.run(block_config, api));
 
         let block = Block {
@@ -288,13 +509,27 @@ impl BarState {
             widget_updates_sender: self.widget_updates_sender.clone(),
             abort_handle,
 
-            click_handler: common_config.click,
+            click_handler,
             signal: common_config.signal,
             shared_config,
+            theme_overrides,
+            command_timeout: Duration::from_secs(common_config.command_timeout),
 
             error_format,
             error_fullscreen_format,
 
+            format: None,
+            format_override: None,
+            format_alt,
+            format_expanded,
+            expanded: false,
+            format_cycle: 0,
+            group: common_config.group,
+            hidden: None,
+            flash: None,
+            flash_id: 0,
+            confirming: None,
+
             state: BlockState::None,
         };
 
@@ -311,44 +546,136 @@ impl BarState {
     fn process_request(&mut self, request: Request) {
         let block = &mut self.blocks[request.block_id].0;
         match request.cmd {
-            RequestCmd::SetWidget(widget) => {
-                block.state = BlockState::Normal { widget };
-                if self.fullscreen_block == Some(request.block_id) {
+            RequestCmd::SetWidget(mut widget) => {
+                // The error view is closed once the block recovers
+                if matches!(block.state, BlockState::Error { .. })
+                    && self.fullscreen_block == Some(request.block_id)
+                {
                     self.fullscreen_block = None;
+                    block.expanded = false;
+                }
+                if widget.has_main_format() {
+                    block.format = widget.format().cloned();
+                    if let Some(format_replacement) = block.format_replacement() {
+                        if block.format.is_some() {
+                            widget.set_format(format_replacement.clone());
+                        }
+                    }
                 }
+                block.state = BlockState::Normal { widget };
             }
             RequestCmd::UnsetWidget => {
                 block.state = BlockState::None;
                 if self.fullscreen_block == Some(request.block_id) {
                     self.fullscreen_block = None;
+                    block.expanded = false;
                 }
             }
             RequestCmd::SetError(error) => {
                 block.set_error(self.fullscreen_block == Some(request.block_id), error);
             }
+            RequestCmd::Flash {
+                text,
+                duration,
+                state,
+            } => {
+                block.flash(&text, duration, state, &self.request_sender);
+            }
+            RequestCmd::EndFlash(id) => {
+                if block
+                    .flash
+                    .as_ref()
+                    .is_some_and(|(flash_id, _)| *flash_id == id)
+                {
+                    block.flash = None;
+                }
+            }
         }
         block.notify_intervals();
     }
 
+    /// Show only the block `id`, or show all blocks again if it is already expanded
+    fn toggle_expanded(&mut self, id: usize) -> Result<()> {
+        if let Some(previous) = toggle_expanded(&mut self.blocks, &mut self.fullscreen_block, id) {
+            self.render_block(previous)?;
+        }
+        Ok(())
+    }
+
     fn render_block(&mut self, id: usize) -> Result<()> {
         let (block, block_type) = &mut self.blocks[id];
         let data = &mut self.blocks_render_cache[id];
+        let theme = block.shared_config.theme.get(self.dark);
+        if let Some((_, widget)) = &block.flash {
+            *data = widget.get_data(theme).in_block(*block_type, id)?;
+            return Ok(());
+        }
         match &block.state {
             BlockState::None => {
                 data.clear();
             }
             BlockState::Normal { widget } | BlockState::Error { widget, .. } => {
-                *data = widget.get_data().in_block(*block_type, id)?;
+                *data = widget.get_data(theme).in_block(*block_type, id)?;
+                if let Some(hidden) = &block.hidden {
+                    if hidden == data {
+                        data.clear();
+                    } else {
+                        block.hidden = None;
+                    }
+                }
             }
         }
         Ok(())
     }
 
+    fn block_output(&self, id: usize) -> BlockOutput<'_> {
+        BlockOutput {
+            widgets: &self.blocks_render_cache[id],
+            group: self.blocks[id].0.group.as_deref(),
+            theme: self.blocks[id].0.shared_config.theme.get(self.dark),
+        }
+    }
+
+    /// Switch between the light and dark themes
+    fn set_dark(&mut self, dark: bool) -> Result<()> {
+        self.dark = dark;
+        for id in 0..self.blocks.len() {
+            self.render_block(id)?;
+        }
+        self.render();
+        Ok(())
+    }
+
+    /// Read the watched palette again and apply it to the theme of every block. On error, the
+    /// current themes are kept.
+    fn reload_palette(&mut self) -> Result<()> {
+        let theme = self.config.shared.theme.reload()?;
+        let block_themes = self
+            .blocks
+            .iter()
+            .map(|(block, block_type)| {
+                block_theme(&theme, *block_type, block.theme_overrides.as_ref())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.config.shared.theme = Arc::new(theme);
+        for ((block, _), theme) in self.blocks.iter_mut().zip(block_themes) {
+            block.shared_config.theme = Arc::new(theme);
+        }
+        for id in 0..self.blocks.len() {
+            self.render_block(id)?;
+        }
+        self.render();
+        Ok(())
+    }
+
     fn render(&self) {
         if let Some(id) = self.fullscreen_block {
-            protocol::print_blocks(&[self.blocks_render_cache[id].clone()], &self.config.shared);
+            protocol::print_blocks(&[self.block_output(id)]);
         } else {
-            protocol::print_blocks(&self.blocks_render_cache, &self.config.shared);
+            let blocks: Vec<_> = (0..self.blocks.len())
+                .map(|id| self.block_output(id))
+                .collect();
+            protocol::print_blocks(&blocks);
         }
     }
 
@@ -375,12 +702,34 @@ impl BarState {
                 Ok(())
             }
             // Handle clicks
-            Some(event) = self.events_stream.next() => {
+            Some(mut event) = self.events_stream.next() => {
                 let (block, block_type) = self.blocks.get_mut(event.id).error("Events receiver: ID out of bounds")?;
+                let confirmed = !matches!(block.state, BlockState::Normal { .. })
+                    || block.confirm_click(&event, &self.request_sender);
                 match &mut block.state {
                     BlockState::None => (),
-                    BlockState::Normal { .. } => {
-                        let post_actions = block.click_handler.handle(event.button).await.in_block(*block_type, event.id)?;
+                    BlockState::Normal { .. } if !confirmed => {
+                        self.render_block(event.id)?;
+                        self.render();
+                    }
+                    BlockState::Normal { widget } => {
+                        if event.instance == Some(SLIDER_INSTANCE) {
+                            event.slider = widget.slider_range();
+                        }
+                        let block_name = format!("{block_type:?}");
+                        let flash_target = click::FlashTarget {
+                            block_id: event.id,
+                            request_sender: self.request_sender.clone(),
+                            timeout: block.command_timeout,
+                        };
+                        let post_actions = block.click_handler.handle(&event, &block_name, Some(widget), &flash_target).await.in_block(*block_type, event.id)?;
+                        let block_type = *block_type;
+                        if block.run_builtin_actions(&post_actions, self.dark, &flash_target).in_block(block_type, event.id)? {
+                            self.toggle_expanded(event.id)?;
+                        }
+                        self.render_block(event.id)?;
+                        self.render();
+                        let block = &self.blocks[event.id].0;
                         if let Some(sender) = &block.event_sender {
                             if post_actions.pass {
                                 let _ = sender.send(BlockEvent::Click(event)).await;
@@ -405,6 +754,15 @@ impl BarState {
                 }
                 Ok(())
             }
+            // Switch between the light and dark themes
+            Some(dark) = self.dark_mode_stream.next() => self.set_dark(dark),
+            // Apply the changed palette to the theme of every block
+            Some(()) = self.palette_updates_stream.next() => {
+                if let Err(error) = self.reload_palette() {
+                    eprintln!("Warning: failed to apply the changed palette: {error}");
+                }
+                Ok(())
+            }
             // Handle signals
             Some(signal) = self.signals_stream.next() => match signal {
                 Signal::Usr1 => {
@@ -416,6 +774,9 @@ impl BarState {
                     Ok(())
                 }
                 Signal::Usr2 => restart(),
+                Signal::Custom(signal) if self.config.dark_mode_signal == Some(signal) => {
+                    self.set_dark(!self.dark)
+                }
                 Signal::Custom(signal) => {
                     for (block, _) in &self.blocks {
                         if let Some(sender) = &block.event_sender {
@@ -457,6 +818,40 @@ impl BarState {
     }
 }
 
+/// The theme of a block: the bar's theme with the `[theme.block.<block>]` sections and the block's
+/// own `theme_overrides` applied
+fn block_theme(
+    theme: &ThemeSet,
+    block_type: BlockType,
+    overrides: Option<&HashMap<String, String>>,
+) -> Result<ThemeSet> {
+    let mut theme = theme.clone();
+    theme.apply_block_overrides(&format!("{block_type:?}"))?;
+    if let Some(overrides) = overrides {
+        theme.apply_overrides(overrides)?;
+    }
+    Ok(theme)
+}
+
+/// The formats set in a block's configuration, i.e. the `format`, `format_*` and `*_format` keys.
+/// Values which cannot be parsed are skipped, the block reports them itself.
+fn configured_formats(block_config: &toml::Value) -> Vec<(String, Format)> {
+    let table = match block_config.as_table() {
+        Some(table) => table,
+        None => return Vec::new(),
+    };
+    table
+        .iter()
+        .filter(|(key, _)| {
+            *key == "format" || key.starts_with("format_") || key.ends_with("_format")
+        })
+        .filter_map(|(key, value)| {
+            let format = FormatConfig::deserialize(value.clone()).ok()?;
+            Some((key.clone(), format.with_default("").ok()?))
+        })
+        .collect()
+}
+
 /// Restart in-place
 fn restart() -> ! {
     use std::env;
@@ -481,3 +876,209 @@ fn restart() -> ! {
     nix::unistd::execvp(&exe, &arg).unwrap();
     unreachable!();
 }
+
+/// Expand the block `id`, or collapse it if it is the `expanded` one. Expanding a block collapses
+/// the previously expanded block, which is returned so that it can be rendered again.
+fn toggle_expanded(
+    blocks: &mut [(Block, BlockType)],
+    expanded: &mut Option<usize>,
+    id: usize,
+) -> Option<usize> {
+    let previous = expanded.take();
+    if let Some(previous) = previous {
+        blocks[previous].0.set_expanded(false);
+    }
+    if previous != Some(id) {
+        *expanded = Some(id);
+        blocks[id].0.set_expanded(true);
+    }
+    previous
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(text: &str) -> Format {
+        FormatConfig::default().with_default(text).unwrap()
+    }
+
+    /// A block which shows `main` and is not connected to a running block
+    fn test_block(main: &Format) -> Block {
+        let widget = Widget::new(0, SharedConfig::default()).with_format(main.clone());
+        Block {
+            id: 0,
+
+            event_sender: None,
+            widget_updates_sender: mpsc::unbounded_channel().0,
+            abort_handle: AbortHandle::new_pair().0,
+
+            click_handler: ClickHandler::default(),
+            signal: None,
+            shared_config: SharedConfig::default(),
+            theme_overrides: None,
+            command_timeout: Duration::from_secs(1),
+
+            error_format: format(""),
+            error_fullscreen_format: format(""),
+
+            format: Some(main.clone()),
+            format_override: None,
+            format_alt: None,
+            format_expanded: None,
+            expanded: false,
+            format_cycle: 0,
+            group: None,
+            hidden: None,
+            flash: None,
+            flash_id: 0,
+            confirming: None,
+
+            state: BlockState::Normal { widget },
+        }
+    }
+
+    fn shown_format(block: &Block) -> Format {
+        match &block.state {
+            BlockState::Normal { widget } => widget.format().unwrap().clone(),
+            _ => panic!("the block has no widget"),
+        }
+    }
+
+    #[test]
+    fn builtin_actions() {
+        let main = format("main");
+        let alt = format("alt");
+        let cycle = vec![format("one"), format("two")];
+        let mut block = test_block(&main);
+        block.format_alt = Some(alt.clone());
+        let target = click::FlashTarget {
+            block_id: 0,
+            request_sender: mpsc::channel(1).0,
+            timeout: Duration::from_secs(1),
+        };
+        let run = |block: &mut Block, actions: PostActions| {
+            block.run_builtin_actions(&actions, false, &target).unwrap()
+        };
+        let toggle = PostActions {
+            toggle_format_alt: true,
+            ..Default::default()
+        };
+        let next = PostActions {
+            cycle_format: Some(cycle.clone()),
+            ..Default::default()
+        };
+
+        run(&mut block, toggle.clone());
+        assert!(Arc::ptr_eq(&shown_format(&block), &alt));
+        run(&mut block, toggle);
+        assert!(Arc::ptr_eq(&shown_format(&block), &main));
+
+        run(&mut block, next.clone());
+        assert!(Arc::ptr_eq(&shown_format(&block), &cycle[0]));
+        run(&mut block, next.clone());
+        assert!(Arc::ptr_eq(&shown_format(&block), &cycle[1]));
+        run(&mut block, next);
+        assert!(Arc::ptr_eq(&shown_format(&block), &main));
+
+        let hide = PostActions {
+            hide: true,
+            ..Default::default()
+        };
+        assert!(!run(&mut block, hide));
+        assert!(block.hidden.is_some());
+        let expand = PostActions {
+            expand: true,
+            ..Default::default()
+        };
+        assert!(run(&mut block, expand));
+    }
+
+    fn click(button: MouseButton) -> I3BarEvent {
+        I3BarEvent {
+            id: 0,
+            instance: None,
+            button,
+            modifiers: Vec::new(),
+            x: 0.,
+            y: 0.,
+            relative_x: 0.,
+            relative_y: 0.,
+            width: 0.,
+            height: 0.,
+            slider: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn confirm_click() {
+        let main = format("main");
+        let mut block = test_block(&main);
+        let entries: toml::Value =
+            toml::from_str(r#"click = [{ button = "left", cmd = "true", confirm = "Sure?" }]"#)
+                .unwrap();
+        block.click_handler = entries["click"].clone().try_into().unwrap();
+        let (sender, _receiver) = mpsc::channel(8);
+        let left = click(MouseButton::Left);
+        let right = click(MouseButton::Right);
+
+        // The first click asks, the same button confirms
+        assert!(!block.confirm_click(&left, &sender));
+        assert!(block.flash.is_some());
+        assert!(block.confirm_click(&left, &sender));
+        assert!(block.flash.is_none() && block.confirming.is_none());
+
+        // Another button cancels without being handled
+        assert!(!block.confirm_click(&left, &sender));
+        assert!(!block.confirm_click(&right, &sender));
+        assert!(block.flash.is_none() && block.confirming.is_none());
+
+        // Once the prompt expired (as on `RequestCmd::EndFlash`), the next click asks again
+        assert!(!block.confirm_click(&left, &sender));
+        block.flash = None;
+        assert!(!block.confirm_click(&left, &sender));
+        assert!(block.confirming.is_some());
+
+        // So does it once another flash replaced the prompt
+        block.flash("other", Duration::from_secs(1), State::Info, &sender);
+        assert!(!block.confirm_click(&left, &sender));
+        assert!(block.confirm_click(&left, &sender));
+    }
+
+    #[test]
+    fn expanded_blocks() {
+        let (main, expanded) = (format("main"), format("expanded"));
+        let mut blocks: Vec<_> = (0..2)
+            .map(|id| {
+                let mut block = test_block(&main);
+                block.id = id;
+                block.format_expanded = Some(expanded.clone());
+                (block, BlockType::time)
+            })
+            .collect();
+        let shown = |blocks: &[(Block, BlockType)], id: usize| shown_format(&blocks[id].0);
+        let mut fullscreen = None;
+
+        assert_eq!(toggle_expanded(&mut blocks, &mut fullscreen, 0), None);
+        assert!(Arc::ptr_eq(&shown(&blocks, 0), &expanded));
+        assert!(Arc::ptr_eq(&shown(&blocks, 1), &main));
+
+        // Expanding another block collapses the first one
+        assert_eq!(toggle_expanded(&mut blocks, &mut fullscreen, 1), Some(0));
+        assert!(Arc::ptr_eq(&shown(&blocks, 0), &main));
+        assert!(Arc::ptr_eq(&shown(&blocks, 1), &expanded));
+        assert!(!blocks[0].0.expanded && blocks[1].0.expanded);
+
+        assert_eq!(toggle_expanded(&mut blocks, &mut fullscreen, 1), Some(1));
+        assert!(Arc::ptr_eq(&shown(&blocks, 1), &main));
+        assert_eq!(fullscreen, None);
+
+        // A `format_override` (e.g. from `format_alt`) is shown again once collapsed
+        let alt = format("alt");
+        blocks[0].0.format_override = Some(alt.clone());
+        blocks[0].0.set_expanded(true);
+        assert!(Arc::ptr_eq(&shown(&blocks, 0), &expanded));
+        blocks[0].0.set_expanded(false);
+        assert!(Arc::ptr_eq(&shown(&blocks, 0), &alt));
+    }
+}
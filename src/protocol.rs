@@ -1,8 +1,8 @@
 pub mod i3bar_block;
 pub mod i3bar_event;
 
-use crate::config::SharedConfig;
 use crate::themes::color::Color;
+use crate::themes::Theme;
 
 use i3bar_block::I3BarBlock;
 
@@ -14,59 +14,305 @@ pub fn init(never_pause: bool) {
     }
 }
 
-pub fn print_blocks(blocks: &[Vec<I3BarBlock>], config: &SharedConfig) {
-    let mut last_bg = Color::None;
+/// The output of a block, with the settings which affect how it is joined with other blocks
+pub struct BlockOutput<'a> {
+    pub widgets: &'a [I3BarBlock],
+    /// Alternating tints are applied to every second block of the same group
+    pub group: Option<&'a str>,
+    /// The block's theme, including its `theme_overrides`
+    pub theme: &'a Theme,
+}
+
+/// Which side of a block a separator is placed on
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Start,
+    End,
+}
+
+/// Build a separator between the widgets with the backgrounds `left` and `right`. `thin` is used
+/// instead of `text` if both sides have the same background, and is colored like the text of
+/// `text_fg`.
+fn separator(
+    theme: &Theme,
+    side: Side,
+    text: &str,
+    thin: Option<&str>,
+    (left, right): (Color, Color),
+    text_fg: Color,
+) -> I3BarBlock {
+    let (full_text, fg, bg) = match thin {
+        Some(thin) if left == right => (thin, text_fg, left),
+        // A separator at the start of a block points to the left, so it is drawn in the color of
+        // the block on its right, and the other way round at the end of a block
+        _ if side == Side::Start => (text, right, left),
+        _ => (text, left, right),
+    };
+    I3BarBlock {
+        full_text: full_text.to_string(),
+        background: if theme.separator_bg == Color::Auto {
+            bg
+        } else {
+            theme.separator_bg
+        },
+        color: if theme.separator_fg == Color::Auto {
+            fg
+        } else {
+            theme.separator_fg
+        },
+        ..Default::default()
+    }
+}
+
+pub fn print_blocks(blocks: &[BlockOutput]) {
+    println!(
+        "{},",
+        serde_json::to_string(&render_blocks(blocks)).unwrap()
+    );
+}
+
+/// The widgets of all blocks, joined with separators and caps
+fn render_blocks(blocks: &[BlockOutput]) -> Vec<I3BarBlock> {
     let mut rendered_blocks = vec![];
 
-    // The right most block should never be alternated
-    let mut alt = blocks.iter().filter(|x| !x.is_empty()).count() % 2 == 0;
-
-    for mut widgets in blocks.iter().filter(|x| !x.is_empty()).cloned() {
-        // Apply tint for all widgets of every second block
-        // TODO: Allow for other non-additive tints
-        if alt {
-            for data in &mut widgets {
-                data.background = data.background + config.theme.alternating_tint_bg;
-                data.color = data.color + config.theme.alternating_tint_fg;
-            }
+    let blocks: Vec<_> = blocks.iter().filter(|x| !x.widgets.is_empty()).collect();
+
+    // Every second block of a group is tinted, counting from the right. The right most block of
+    // a group is never tinted.
+    let mut tinted = vec![false; blocks.len()];
+    for i in (0..blocks.len()).rev() {
+        tinted[i] = blocks
+            .get(i + 1)
+            .is_some_and(|next| next.group == blocks[i].group && !tinted[i + 1]);
+    }
+
+    let widgets: Vec<Vec<I3BarBlock>> = blocks
+        .iter()
+        .zip(tinted)
+        .map(|(block, tinted)| tint(block, tinted))
+        .collect();
+    let first = |i: usize| widgets[i].first().unwrap();
+    let last = |i: usize| widgets[i].last().unwrap();
+
+    for (i, block) in blocks.iter().enumerate() {
+        let theme = block.theme;
+        let prev = i.checked_sub(1);
+        let next = Some(i + 1).filter(|&next| next < blocks.len());
+        let group_start = prev.is_none_or(|prev| blocks[prev].group != block.group);
+        let group_end = next.is_none_or(|next| blocks[next].group != block.group);
+        let prev_bg = prev.map_or(Color::None, |prev| last(prev).background);
+        let next_bg = next.map_or(Color::None, |next| first(next).background);
+
+        let start = match (&theme.start_cap, &theme.separator) {
+            (Some(cap), _) if group_start => Some((cap, None, Color::None)),
+            (_, Some(separator)) => Some((separator, theme.thin_separator.as_deref(), prev_bg)),
+            _ => None,
+        };
+        if let Some((text, thin, left)) = start {
+            let right = first(i);
+            rendered_blocks.push(separator(
+                theme,
+                Side::Start,
+                text,
+                thin,
+                (left, right.background),
+                right.color,
+            ));
         }
-        alt = !alt;
-
-        if let Some(separator) = &config.theme.separator {
-            // The first widget's BG is used to get the FG color for the current separator
-            let sep_fg = if config.theme.separator_fg == Color::Auto {
-                widgets.first().unwrap().background
-            } else {
-                config.theme.separator_fg
-            };
-
-            // The separator's BG is the last block's last widget's BG
-            let sep_bg = if config.theme.separator_bg == Color::Auto {
-                last_bg
-            } else {
-                config.theme.separator_bg
-            };
-
-            // The last widget's BG is used to get the BG color for the next separator
-            last_bg = widgets.last().unwrap().background;
-
-            let separator = I3BarBlock {
-                full_text: separator.clone(),
-                background: sep_bg,
-                color: sep_fg,
-                ..Default::default()
-            };
-
-            rendered_blocks.push(separator);
-            rendered_blocks.extend(widgets);
-        } else {
+
+        let mut block_widgets = widgets[i].clone();
+        if theme.separator.is_none() && theme.end_separator.is_none() {
             // Re-add native separator on last widget for native theme
-            widgets.last_mut().unwrap().separator = None;
-            widgets.last_mut().unwrap().separator_block_width = None;
+            block_widgets.last_mut().unwrap().separator = None;
+            block_widgets.last_mut().unwrap().separator_block_width = None;
+        }
+        rendered_blocks.extend(block_widgets);
+
+        let end = match (&theme.end_cap, &theme.end_separator) {
+            (Some(cap), _) if group_end => Some((cap, None, Color::None)),
+            (_, Some(separator)) => Some((separator, theme.thin_end_separator.as_deref(), next_bg)),
+            _ => None,
+        };
+        if let Some((text, thin, right)) = end {
+            let left = last(i);
+            rendered_blocks.push(separator(
+                theme,
+                Side::End,
+                text,
+                thin,
+                (left.background, right),
+                left.color,
+            ));
+        }
+    }
+
+    rendered_blocks
+}
 
-            rendered_blocks.extend(widgets);
+/// Apply the alternating tint of the block's theme to its widgets
+fn tint(block: &BlockOutput, tinted: bool) -> Vec<I3BarBlock> {
+    let theme = block.theme;
+    let mut widgets = block.widgets.to_vec();
+    if tinted {
+        for data in &mut widgets {
+            data.background = data
+                .background
+                .blend(theme.alternating_tint_bg, theme.alternating_tint_mode);
+            data.color = data
+                .color
+                .blend(theme.alternating_tint_fg, theme.alternating_tint_mode);
         }
     }
 
-    println!("{},", serde_json::to_string(&rendered_blocks).unwrap());
+    widgets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(color: &str) -> Color {
+        color.parse().unwrap()
+    }
+
+    fn widget(text: &str, bg: &str, fg: &str) -> I3BarBlock {
+        I3BarBlock {
+            full_text: text.to_string(),
+            background: color(bg),
+            color: color(fg),
+            ..Default::default()
+        }
+    }
+
+    fn render<'a>(
+        theme: &'a Theme,
+        widgets: &'a [(Option<&'a str>, I3BarBlock)],
+    ) -> Vec<I3BarBlock> {
+        let blocks: Vec<_> = widgets
+            .iter()
+            .map(|(group, widget)| BlockOutput {
+                widgets: std::slice::from_ref(widget),
+                group: *group,
+                theme,
+            })
+            .collect();
+        render_blocks(&blocks)
+    }
+
+    /// The text, background and foreground of each rendered widget
+    fn summary(rendered: &[I3BarBlock]) -> Vec<(&str, Color, Color)> {
+        rendered
+            .iter()
+            .map(|w| (w.full_text.as_str(), w.background, w.color))
+            .collect()
+    }
+
+    fn blocks() -> Vec<(Option<&'static str>, I3BarBlock)> {
+        vec![
+            (None, widget("a", "#111111", "#aaaaaa")),
+            (None, widget("b", "#222222", "#bbbbbb")),
+            (None, widget("c", "#222222", "#cccccc")),
+        ]
+    }
+
+    #[test]
+    fn powerline_separators() {
+        let theme = Theme {
+            separator: Some("\u{e0b2}".into()),
+            separator_bg: color("#000000"),
+            separator_fg: color("#a9a9a9"),
+            ..Default::default()
+        };
+        let sep = ("\u{e0b2}", color("#000000"), color("#a9a9a9"));
+        assert_eq!(
+            summary(&render(&theme, &blocks())),
+            [
+                sep,
+                ("a", color("#111111"), color("#aaaaaa")),
+                sep,
+                ("b", color("#222222"), color("#bbbbbb")),
+                sep,
+                ("c", color("#222222"), color("#cccccc")),
+            ]
+        );
+    }
+
+    #[test]
+    fn native_separators() {
+        let theme = Theme::default();
+        let rendered = render(&theme, &blocks());
+        assert_eq!(rendered.len(), 3);
+        for widget in rendered {
+            assert_eq!(widget.separator, None);
+            assert_eq!(widget.separator_block_width, None);
+        }
+    }
+
+    #[test]
+    fn auto_separator_colors() {
+        let theme = Theme {
+            separator: Some("<".into()),
+            separator_bg: Color::Auto,
+            separator_fg: Color::Auto,
+            ..Default::default()
+        };
+        let rendered = render(&theme, &blocks()[..2]);
+        assert_eq!(
+            summary(&rendered),
+            [
+                ("<", Color::None, color("#111111")),
+                ("a", color("#111111"), color("#aaaaaa")),
+                ("<", color("#111111"), color("#222222")),
+                ("b", color("#222222"), color("#bbbbbb")),
+            ]
+        );
+    }
+
+    #[test]
+    fn thin_separators() {
+        let theme = Theme {
+            separator: Some("<".into()),
+            thin_separator: Some("|".into()),
+            separator_bg: Color::Auto,
+            separator_fg: Color::Auto,
+            ..Default::default()
+        };
+        let rendered = render(&theme, &blocks());
+        // "b" and "c" have the same background, so the thin separator is drawn in the text color
+        // of "c"
+        assert_eq!(
+            summary(&rendered)[4],
+            ("|", color("#222222"), color("#cccccc"))
+        );
+        assert_eq!(summary(&rendered)[2].0, "<");
+    }
+
+    #[test]
+    fn caps() {
+        let theme = Theme {
+            separator: Some("<".into()),
+            start_cap: Some("(".into()),
+            end_cap: Some(")".into()),
+            separator_bg: Color::Auto,
+            separator_fg: Color::Auto,
+            ..Default::default()
+        };
+        let mut blocks = blocks();
+        blocks[0].0 = Some("x");
+        blocks[1].0 = Some("x");
+        let rendered = render(&theme, &blocks);
+        assert_eq!(
+            summary(&rendered),
+            [
+                ("(", Color::None, color("#111111")),
+                ("a", color("#111111"), color("#aaaaaa")),
+                ("<", color("#111111"), color("#222222")),
+                ("b", color("#222222"), color("#bbbbbb")),
+                (")", Color::None, color("#222222")),
+                ("(", Color::None, color("#222222")),
+                ("c", color("#222222"), color("#cccccc")),
+                (")", Color::None, color("#222222")),
+            ]
+        );
+    }
 }
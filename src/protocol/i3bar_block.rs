@@ -2,7 +2,7 @@ use crate::themes::color::Color;
 use serde::Serialize;
 
 /// Represent block as described in <https://i3wm.org/docs/i3bar-protocol.html>
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, PartialEq)]
 pub struct I3BarBlock {
     pub full_text: String,
     #[serde(skip_serializing_if = "String::is_empty")]
@@ -67,7 +67,7 @@ impl Default for I3BarBlock {
     }
 }
 
-#[derive(Serialize, Debug, Clone, Copy)]
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
 #[serde(rename_all = "lowercase")]
 pub enum I3BarBlockAlign {
@@ -76,7 +76,7 @@ pub enum I3BarBlockAlign {
     Left,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
 #[allow(dead_code)]
 #[serde(untagged)]
 pub enum I3BarBlockMinWidth {
@@ -7,14 +7,108 @@ use futures::StreamExt;
 use tokio::fs::File;
 use tokio::io::{AsyncBufReadExt, BufReader};
 
-use crate::click::MouseButton;
+use crate::click::{MouseButton, SLIDER_INSTANCE};
+use crate::errors::*;
 use crate::BoxedStream;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct I3BarEvent {
     pub id: usize,
     pub instance: Option<usize>,
     pub button: MouseButton,
+    /// Names of the modifier keys held during the click, e.g. `Shift`, `Control` or `Mod4`
+    pub modifiers: Vec<String>,
+    /// Position of the click relative to the top left corner of the bar
+    pub x: f64,
+    pub y: f64,
+    /// Position of the click relative to the top left corner of the block
+    pub relative_x: f64,
+    pub relative_y: f64,
+    /// Size of the block
+    pub width: f64,
+    pub height: f64,
+    /// The values of an empty and a full slider, if the click is on a slider. Filled in by the
+    /// bar from the block's widget.
+    pub slider: Option<(f64, f64)>,
+}
+
+impl I3BarEvent {
+    /// Whether both events are clicks of the same button on the same block, regardless of the
+    /// position
+    fn same_click(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.instance == other.instance
+            && self.button == other.button
+            && self.modifiers == other.modifiers
+    }
+
+    /// If this is a left click on a slider (see `Value::with_slider`), returns the value which
+    /// corresponds to the click position, on the scale of the slider (e.g. `0` to `150` for
+    /// `$volume.bar(10, 150)`). Blocks use this to let the user set volume, brightness, etc. by
+    /// clicking on a `bar`.
+    pub fn slider_value(&self) -> Option<f64> {
+        let (min, max) = self.slider?;
+        (self.button == MouseButton::Left
+            && self.instance == Some(SLIDER_INSTANCE)
+            && self.width > 0.)
+            .then(|| min + (self.relative_x / self.width).clamp(0., 1.) * (max - min))
+    }
+}
+
+#[derive(Deserialize)]
+struct I3BarEventRaw {
+    name: Option<String>,
+    instance: Option<String>,
+    button: MouseButton,
+    #[serde(default)]
+    modifiers: Vec<String>,
+    #[serde(default)]
+    x: f64,
+    #[serde(default)]
+    y: f64,
+    #[serde(default)]
+    relative_x: f64,
+    #[serde(default)]
+    relative_y: f64,
+    #[serde(default)]
+    width: f64,
+    #[serde(default)]
+    height: f64,
+}
+
+/// Parse a line sent by i3bar. Returns `Ok(None)` if the event is not for one of our blocks.
+fn parse_event(line: &str, invert_scrolling: bool) -> Result<Option<I3BarEvent>> {
+    let event: I3BarEventRaw = serde_json::from_str(line).error("Invalid JSON")?;
+    let id = match event.name {
+        Some(name) => name.parse().error("Invalid block name")?,
+        None => return Ok(None),
+    };
+    let instance = event
+        .instance
+        .map(|x| x.parse::<usize>())
+        .transpose()
+        .error("Invalid block instance")?;
+
+    use MouseButton::*;
+    let button = match (event.button, invert_scrolling) {
+        (WheelUp, false) | (WheelDown, true) => WheelUp,
+        (WheelUp, true) | (WheelDown, false) => WheelDown,
+        (other, _) => other,
+    };
+
+    Ok(Some(I3BarEvent {
+        id,
+        instance,
+        button,
+        modifiers: event.modifiers,
+        x: event.x,
+        y: event.y,
+        relative_x: event.relative_x,
+        relative_y: event.relative_y,
+        width: event.width,
+        height: event.height,
+        slider: None,
+    }))
 }
 
 fn unprocessed_events_stream(invert_scrolling: bool) -> BoxedStream<I3BarEvent> {
@@ -34,34 +128,11 @@ fn unprocessed_events_stream(invert_scrolling: bool) -> BoxedStream<I3BarEvent>
                 continue;
             }
 
-            #[derive(Deserialize)]
-            struct I3BarEventRaw {
-                name: Option<String>,
-                instance: Option<String>,
-                button: MouseButton,
+            match parse_event(line, invert_scrolling) {
+                Ok(Some(event)) => break Some((event, lines)),
+                Ok(None) => (),
+                Err(e) => eprintln!("Skipping malformed click event '{line}': {e}"),
             }
-
-            let event: I3BarEventRaw = serde_json::from_str(line).unwrap();
-            let id = match event.name {
-                Some(name) => name.parse().unwrap(),
-                None => continue,
-            };
-            let instance = event.instance.map(|x| x.parse::<usize>().unwrap());
-
-            use MouseButton::*;
-            let button = match (event.button, invert_scrolling) {
-                (WheelUp, false) | (WheelDown, true) => WheelUp,
-                (WheelUp, true) | (WheelDown, false) => WheelDown,
-                (other, _) => other,
-            };
-
-            let event = I3BarEvent {
-                id,
-                instance,
-                button,
-            };
-
-            break Some((event, lines));
         }
     })
     .boxed_local()
@@ -83,7 +154,7 @@ pub fn events_stream(
         if event.button == MouseButton::Left && !double_click_delay.is_zero() {
             if let Ok(new_event) = tokio::time::timeout(double_click_delay, events.next()).await {
                 let new_event = new_event?;
-                if event == new_event {
+                if event.same_click(&new_event) {
                     event.button = MouseButton::DoubleLeft;
                 } else {
                     return Some((event, (events, Some(new_event))));
@@ -95,3 +166,34 @@ pub fn events_stream(
     })
     .boxed_local()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let event = parse_event(
+            r#"{"name":"3","instance":"1","button":1,"modifiers":["Shift"],"relative_x":5,"width":20}"#,
+            false,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(event.id, 3);
+        assert_eq!(event.instance, Some(1));
+        assert_eq!(event.modifiers, ["Shift"]);
+        assert_eq!(event.relative_x, 5.);
+        assert_eq!(event.width, 20.);
+
+        assert_eq!(event.slider_value(), None);
+        let event = I3BarEvent {
+            instance: Some(SLIDER_INSTANCE),
+            slider: Some((1000., 10000.)),
+            ..event
+        };
+        assert_eq!(event.slider_value(), Some(3250.));
+
+        assert!(parse_event(r#"{"name":"3","button":1"#, false).is_err());
+        assert!(parse_event(r#"{"name":"cpu","button":1}"#, false).is_err());
+    }
+}
@@ -1,11 +1,20 @@
 use std::io;
 use std::os::unix::process::CommandExt;
-use std::process::{Command, Stdio};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::errors::*;
 
 /// Spawn a new detached process
 pub fn spawn_process(cmd: &str, args: &[&str]) -> io::Result<()> {
     let mut proc = Command::new(cmd);
     proc.args(args);
+    spawn_detached(proc)
+}
+
+fn spawn_detached(mut proc: Command) -> io::Result<()> {
     proc.stdin(Stdio::null());
     proc.stdout(Stdio::null());
     // Safety: libc::daemon() is async-signal-safe
@@ -27,9 +36,21 @@ pub fn spawn_shell(cmd: &str) -> io::Result<()> {
     spawn_process("sh", &["-c", cmd])
 }
 
+/// Spawn a new detached shell with additional environment variables
+pub fn spawn_shell_with_env(cmd: &str, env: &[(&str, String)]) -> io::Result<()> {
+    let mut proc = Command::new("sh");
+    proc.args(["-c", cmd]).envs(env.iter().cloned());
+    spawn_detached(proc)
+}
+
 pub async fn spawn_shell_sync(cmd: &str) -> io::Result<()> {
+    spawn_shell_sync_with_env(cmd, &[]).await
+}
+
+pub async fn spawn_shell_sync_with_env(cmd: &str, env: &[(&str, String)]) -> io::Result<()> {
     tokio::process::Command::new("sh")
         .args(["-c", cmd])
+        .envs(env.iter().cloned())
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .spawn()?
@@ -37,3 +58,168 @@ pub async fn spawn_shell_sync(cmd: &str) -> io::Result<()> {
         .await?;
     Ok(())
 }
+
+/// Copy `text` to the clipboard, using `wl-copy` on Wayland and `xclip` on X11.
+///
+/// Both tools fork a process which keeps serving the clipboard, so only the exit status is waited
+/// for. The tool is killed if it does not exit within `timeout`.
+pub async fn copy_to_clipboard(text: &str, timeout: Duration) -> Result<()> {
+    let mut cmd = if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        tokio::process::Command::new("wl-copy")
+    } else {
+        let mut cmd = tokio::process::Command::new("xclip");
+        cmd.args(["-selection", "clipboard"]);
+        cmd
+    };
+    let name = describe(cmd.as_std());
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .or_error(|| format!("Failed to run '{name}'"))?;
+    let mut stdin = child.stdin.take().error("Failed to open stdin")?;
+    let mut stderr = child.stderr.take().error("Failed to open stderr")?;
+    let result = tokio::time::timeout(timeout, async {
+        stdin.write_all(text.as_bytes()).await?;
+        drop(stdin);
+        let status = child.wait().await?;
+        let mut error = String::new();
+        if !status.success() {
+            stderr.read_to_string(&mut error).await?;
+        }
+        io::Result::Ok((status, error))
+    })
+    .await;
+    let (status, error) = result
+        .map_err(|_| timed_out(&name, timeout))?
+        .or_error(|| format!("Failed to run '{name}'"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(failed(&name, status, &error))
+    }
+}
+
+/// Run `cmd` to completion and return its output, regardless of the exit status.
+///
+/// The command runs in its own process group. The whole group is killed if the command does not
+/// finish within `timeout` or if the returned future is dropped (e.g. when the block is aborted).
+pub async fn run_command(cmd: &mut tokio::process::Command, timeout: Duration) -> Result<Output> {
+    let name = describe(cmd.as_std());
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // Safety: setpgid() is async-signal-safe
+    unsafe {
+        cmd.pre_exec(|| match libc::setpgid(0, 0) {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        });
+    }
+    let child = cmd.spawn().or_error(|| format!("Failed to run '{name}'"))?;
+    let mut group = ProcessGroup(child.id());
+    let output = tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .map_err(|_| timed_out(&name, timeout))?
+        .or_error(|| format!("Failed to run '{name}'"))?;
+    group.0 = None;
+    Ok(output)
+}
+
+/// Run `cmd` like [`run_command`] and return its stdout. If the command exits unsuccessfully,
+/// its stderr is included in the error.
+pub async fn run_command_stdout(
+    cmd: &mut tokio::process::Command,
+    timeout: Duration,
+) -> Result<String> {
+    let output = run_command(cmd, timeout).await?;
+    let name = describe(cmd.as_std());
+    if !output.status.success() {
+        return Err(failed(
+            &name,
+            output.status,
+            &String::from_utf8_lossy(&output.stderr),
+        ));
+    }
+    String::from_utf8(output.stdout).or_error(|| format!("The output of '{name}' is invalid UTF-8"))
+}
+
+/// Run `cmd` like [`run_command_stdout`] from synchronous code, e.g. while loading the
+/// configuration. The command gets a runtime of its own on another thread, so this works from
+/// within the main runtime too.
+pub fn run_command_stdout_blocking(
+    mut cmd: tokio::process::Command,
+    timeout: Duration,
+) -> Result<String> {
+    std::thread::spawn(move || {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .error("Failed to create a runtime")?
+            .block_on(run_command_stdout(&mut cmd, timeout))
+    })
+    .join()
+    .map_err(|_| Error::new("Failed to run a command"))?
+}
+
+fn timed_out(name: &str, timeout: Duration) -> Error {
+    Error::new(format!(
+        "'{name}' timed out after {}s",
+        timeout.as_secs_f64()
+    ))
+}
+
+/// The error for a command which exited unsuccessfully, including its stderr
+fn failed(name: &str, status: ExitStatus, stderr: &str) -> Error {
+    let stderr = stderr.trim();
+    Error::new(if stderr.is_empty() {
+        format!("'{name}' failed with {status}")
+    } else {
+        format!("'{name}' failed with {status}: {stderr}")
+    })
+}
+
+fn describe(cmd: &Command) -> String {
+    let mut retval = cmd.get_program().to_string_lossy().into_owned();
+    for arg in cmd.get_args() {
+        retval.push(' ');
+        retval.push_str(&arg.to_string_lossy());
+    }
+    retval
+}
+
+/// Kills the process group with the given ID when dropped
+struct ProcessGroup(Option<u32>);
+
+impl Drop for ProcessGroup {
+    fn drop(&mut self) {
+        if let Some(pgid) = self.0 {
+            // Safety: kill() has no memory safety requirements
+            unsafe {
+                libc::kill(-(pgid as libc::pid_t), libc::SIGKILL);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn command_runner() {
+        let run = |script: &str, timeout: u64| {
+            let mut cmd = tokio::process::Command::new("sh");
+            cmd.args(["-c", script]);
+            async move { run_command_stdout(&mut cmd, Duration::from_millis(timeout)).await }
+        };
+        assert_eq!(run("echo ok", 5000).await.unwrap(), "ok\n");
+        let err = run("echo oops >&2; exit 3", 5000).await.unwrap_err();
+        assert!(err.to_string().contains("oops"));
+        let err = run("sleep 5", 100).await.unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+}
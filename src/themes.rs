@@ -1,15 +1,63 @@
 pub mod color;
+pub mod color_scheme;
+pub mod import;
 
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 use crate::errors::*;
 use crate::util;
 use crate::widget::State;
-use color::Color;
+use color::{BlendMode, Color};
 
+/// A theme with an optional dark variant. The theme is chosen each time a block is rendered, so
+/// that switching between light and dark does not require restarting the blocks.
 #[derive(Deserialize, Debug, Clone, Default)]
 #[serde(try_from = "ThemeConfigRaw")]
+pub struct ThemeSet {
+    pub light: Theme,
+    pub dark: Option<Theme>,
+    /// The imported palette, if it should be applied again when it changes
+    pub watched_palette: Option<PathBuf>,
+    /// The configuration this was built from, to build it again with a changed palette
+    config: Arc<ThemeConfigRaw>,
+}
+
+impl ThemeSet {
+    /// The dark theme if `dark` is set and there is one, the light theme otherwise
+    pub fn get(&self, dark: bool) -> &Theme {
+        match &self.dark {
+            Some(theme) if dark => theme,
+            _ => &self.light,
+        }
+    }
+
+    /// Build the themes again from the configuration, reading the palette again
+    pub fn reload(&self) -> Result<Self> {
+        Self::try_from((*self.config).clone())
+    }
+
+    pub fn apply_overrides(&mut self, overrides: &HashMap<String, String>) -> Result<()> {
+        self.light.apply_overrides(overrides)?;
+        if let Some(dark) = &mut self.dark {
+            dark.apply_overrides(overrides)?;
+        }
+        Ok(())
+    }
+
+    /// Apply the overrides of the `[block.<block>]` sections
+    pub fn apply_block_overrides(&mut self, block: &str) -> Result<()> {
+        self.light.apply_block_overrides(block)?;
+        if let Some(dark) = &mut self.dark {
+            dark.apply_block_overrides(block)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Theme {
     pub idle_bg: Color,
     pub idle_fg: Color,
@@ -21,23 +69,108 @@ pub struct Theme {
     pub warning_fg: Color,
     pub critical_bg: Color,
     pub critical_fg: Color,
+    /// Colors of the semantic states, which fall back to the colors of a basic state if not set
+    pub muted_bg: Option<Color>,
+    pub muted_fg: Option<Color>,
+    pub disconnected_bg: Option<Color>,
+    pub disconnected_fg: Option<Color>,
+    /// Placed to the left of each block
     pub separator: Option<String>,
+    /// Placed to the right of each block
+    pub end_separator: Option<String>,
+    /// Used instead of `separator` and `end_separator` between blocks with the same background
+    pub thin_separator: Option<String>,
+    pub thin_end_separator: Option<String>,
+    /// Placed to the left of the first and to the right of the last block of a group
+    pub start_cap: Option<String>,
+    pub end_cap: Option<String>,
     pub separator_bg: Color,
     pub separator_fg: Color,
     pub alternating_tint_bg: Color,
     pub alternating_tint_fg: Color,
+    pub alternating_tint_mode: BlendMode,
+    /// Named colors, which can be referred to as `$name`
+    pub palette: HashMap<String, Color>,
+    /// Overrides for all blocks of a type, applied when a block of this type is created
+    pub block_overrides: HashMap<String, HashMap<String, String>>,
+}
+
+/// Theme overrides, with optional `[block.<block>]` sections which only apply to blocks of that
+/// type
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ThemeOverrides {
+    #[serde(default)]
+    pub block: HashMap<String, HashMap<String, String>>,
+    #[serde(flatten)]
+    pub colors: HashMap<String, String>,
+}
+
+/// The contents of a theme file
+#[derive(Deserialize)]
+struct ThemeFile {
+    /// Another theme, which this file overrides
+    extends: Option<String>,
+    #[serde(default)]
+    palette: HashMap<String, String>,
+    #[serde(flatten)]
+    overrides: ThemeOverrides,
 }
 
 impl Theme {
     pub fn from_file(file: &str) -> Result<Theme> {
-        let file = util::find_file(file, Some("themes"), Some("toml"))
+        Self::from_file_extended(file, 0)
+    }
+
+    fn from_file_extended(file: &str, depth: usize) -> Result<Theme> {
+        if depth > 16 {
+            return Err(Error::new(format!(
+                "Theme '{file}' is extended too many times (do themes extend each other?)"
+            )));
+        }
+        let path = util::find_file(file, Some("themes"), Some("toml"))
             .or_error(|| format!("Theme '{}' not found", file))?;
-        let map: HashMap<String, String> = util::deserialize_toml_file(&file)?;
-        let mut theme = Self::default();
-        theme.apply_overrides(&map)?;
+        let theme_file: ThemeFile = util::deserialize_toml_file(&path)?;
+        let mut theme = match &theme_file.extends {
+            Some(parent) => Self::from_file_extended(parent, depth + 1)?,
+            None => Self::default(),
+        };
+        theme.add_palette(&theme_file.palette)?;
+        theme.apply_theme_overrides(&theme_file.overrides)?;
         Ok(theme)
     }
 
+    /// Add named colors. They may refer to each other and to the colors already in the palette.
+    fn add_palette(&mut self, palette: &HashMap<String, String>) -> Result<()> {
+        let mut pending: Vec<_> = palette.iter().collect();
+        while !pending.is_empty() {
+            let mut unresolved = Vec::new();
+            let mut error = None;
+            for (name, expr) in &pending {
+                match Color::parse_expr(expr, &|name| self.palette_color(name)) {
+                    Ok(color) => {
+                        self.palette.insert(name.to_string(), color);
+                    }
+                    Err(e) => {
+                        unresolved.push((*name, *expr));
+                        error = Some(e);
+                    }
+                }
+            }
+            if unresolved.len() == pending.len() {
+                return Err(error.unwrap());
+            }
+            pending = unresolved;
+        }
+        Ok(())
+    }
+
+    fn palette_color(&self, name: &str) -> Result<Color> {
+        self.palette
+            .get(name)
+            .copied()
+            .or_error(|| format!("Color '${name}' is not in the palette"))
+    }
+
     pub fn get_colors(&self, state: State) -> (Color, Color) {
         match state {
             State::Idle => (self.idle_bg, self.idle_fg),
@@ -45,6 +178,32 @@ impl Theme {
             State::Good => (self.good_bg, self.good_fg),
             State::Warning => (self.warning_bg, self.warning_fg),
             State::Critical => (self.critical_bg, self.critical_fg),
+            State::Muted => (
+                self.muted_bg.unwrap_or(self.warning_bg),
+                self.muted_fg.unwrap_or(self.warning_fg),
+            ),
+            State::Disconnected => (
+                self.disconnected_bg.unwrap_or(self.idle_bg),
+                self.disconnected_fg.unwrap_or(self.idle_fg),
+            ),
+        }
+    }
+
+    pub fn apply_theme_overrides(&mut self, overrides: &ThemeOverrides) -> Result<()> {
+        self.apply_overrides(&overrides.colors)?;
+        for (block, block_overrides) in &overrides.block {
+            self.block_overrides
+                .entry(block.clone())
+                .or_default()
+                .extend(block_overrides.clone());
+        }
+        Ok(())
+    }
+
+    fn apply_block_overrides(&mut self, block: &str) -> Result<()> {
+        match self.block_overrides.get(block).cloned() {
+            Some(overrides) => self.apply_overrides(&overrides),
+            None => Ok(()),
         }
     }
 
@@ -56,10 +215,30 @@ impl Theme {
                 self.separator = Some(separator.clone());
             }
         }
+        macro_rules! apply_separator {
+            ($prop:tt, $rounded:literal, $angled:literal) => {
+                if let Some(val) = overrides.get(stringify!($prop)) {
+                    self.$prop = match val.as_str() {
+                        "none" => None,
+                        "rounded" => Some($rounded.into()),
+                        "angled" => Some($angled.into()),
+                        _ => Some(val.clone()),
+                    };
+                }
+            };
+        }
+        apply_separator!(end_separator, "\u{e0b4}", "\u{e0b0}");
+        apply_separator!(thin_separator, "\u{e0b7}", "\u{e0b3}");
+        apply_separator!(thin_end_separator, "\u{e0b5}", "\u{e0b1}");
+        apply_separator!(start_cap, "\u{e0b6}", "\u{e0b2}");
+        apply_separator!(end_cap, "\u{e0b4}", "\u{e0b0}");
+        if let Some(mode) = overrides.get("alternating_tint_mode") {
+            self.alternating_tint_mode = mode.parse()?;
+        }
         macro_rules! apply {
             ($prop:tt) => {
                 if let Some(val) = overrides.get(stringify!($prop)) {
-                    self.$prop = val.parse()?;
+                    self.$prop = Color::parse_expr(val, &|name| self.palette_color(name))?;
                 }
             };
         }
@@ -73,6 +252,17 @@ impl Theme {
         apply!(warning_fg);
         apply!(critical_bg);
         apply!(critical_fg);
+        macro_rules! apply_optional {
+            ($prop:tt) => {
+                if let Some(val) = overrides.get(stringify!($prop)) {
+                    self.$prop = Some(Color::parse_expr(val, &|name| self.palette_color(name))?);
+                }
+            };
+        }
+        apply_optional!(muted_bg);
+        apply_optional!(muted_fg);
+        apply_optional!(disconnected_bg);
+        apply_optional!(disconnected_fg);
         apply!(separator_bg);
         apply!(separator_fg);
         apply!(alternating_tint_bg);
@@ -81,21 +271,88 @@ impl Theme {
     }
 }
 
-#[derive(Deserialize, Default)]
+#[derive(Deserialize, Debug, Clone, Default)]
 #[serde(deny_unknown_fields, default)]
 struct ThemeConfigRaw {
     theme: Option<String>,
-    overrides: Option<HashMap<String, String>>,
+    overrides: Option<ThemeOverrides>,
+    dark_theme: Option<String>,
+    dark_overrides: Option<ThemeOverrides>,
+    source: Option<import::PaletteSource>,
+    source_file: Option<String>,
+    mapping: Option<HashMap<String, String>>,
+    watch: bool,
 }
 
-impl TryFrom<ThemeConfigRaw> for Theme {
+impl TryFrom<ThemeConfigRaw> for ThemeSet {
     type Error = Error;
 
     fn try_from(raw: ThemeConfigRaw) -> Result<Self, Self::Error> {
-        let mut theme = Self::from_file(raw.theme.as_deref().unwrap_or("plain"))?;
+        let theme = raw.theme.as_deref().unwrap_or("plain");
+
+        let palette_file = raw
+            .source
+            .and_then(|source| import::palette_file(source, raw.source_file.as_deref()));
+        let palette = match raw.source {
+            Some(source) => Some(import::palette_overrides(
+                source,
+                palette_file.as_deref(),
+                raw.mapping.as_ref(),
+            )?),
+            None => None,
+        };
+        let watched_palette = match (raw.watch, palette_file) {
+            (false, _) => None,
+            (true, Some(file)) => Some(file),
+            (true, None) => return Err(Error::new("'watch' requires a palette 'source_file'")),
+        };
+
+        let dark = match (&raw.dark_theme, &raw.dark_overrides) {
+            (None, None) => None,
+            (dark_theme, dark_overrides) => {
+                let mut dark = Theme::from_file(dark_theme.as_deref().unwrap_or(theme))?;
+                if let Some(palette) = &palette {
+                    dark.apply_overrides(palette)?;
+                }
+                if let Some(overrides) = &raw.overrides {
+                    dark.apply_theme_overrides(overrides)?;
+                }
+                if let Some(dark_overrides) = dark_overrides {
+                    dark.apply_theme_overrides(dark_overrides)?;
+                }
+                Some(dark)
+            }
+        };
+        let mut light = Theme::from_file(theme)?;
+        if let Some(palette) = &palette {
+            light.apply_overrides(palette)?;
+        }
         if let Some(overrides) = &raw.overrides {
-            theme.apply_overrides(overrides)?;
+            light.apply_theme_overrides(overrides)?;
         }
-        Ok(theme)
+        Ok(Self {
+            light,
+            dark,
+            watched_palette,
+            config: Arc::new(raw),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_sections_and_states() {
+        let overrides: ThemeOverrides =
+            toml::from_str("warning_bg = \"#ff0000\"\n[block.sound]\nmuted_bg = \"#0000ff\"\n")
+                .unwrap();
+        let mut theme = Theme::default();
+        theme.apply_theme_overrides(&overrides).unwrap();
+        assert_eq!(theme.get_colors(State::Muted).0, theme.warning_bg);
+
+        theme.apply_block_overrides("sound").unwrap();
+        assert_eq!(theme.get_colors(State::Muted).0, "#0000ff".parse().unwrap());
     }
 }
@@ -20,6 +20,160 @@ impl Color {
     pub fn skip_ser(&self) -> bool {
         matches!(self, Self::None | Self::Auto)
     }
+
+    /// Parse a color which may refer to named colors as `$name` (looked up with `palette`) and
+    /// use the functions `darken(color, percent)`, `lighten(color, percent)` and
+    /// `alpha(color, percent)`, e.g. `darken($red, 10)`.
+    pub fn parse_expr(expr: &str, palette: &dyn Fn(&str) -> Result<Color>) -> Result<Color> {
+        let expr = expr.trim();
+        if let Some(name) = expr.strip_prefix('$') {
+            return palette(name);
+        }
+        let call = expr
+            .strip_suffix(')')
+            .and_then(|call| call.split_once('('))
+            .and_then(|(function, args)| Some((function.trim(), args.rsplit_once(',')?)));
+        match call {
+            Some((function, (color, percent))) => {
+                let color = Self::parse_expr(color, palette)?;
+                let percent: f64 = percent
+                    .trim()
+                    .parse()
+                    .or_error(|| format!("'{percent}' is not a valid percentage in '{expr}'"))?;
+                match function {
+                    "darken" => Ok(color.map_hsv(|hsv| {
+                        Hsv::new(hsv.h, hsv.s, (hsv.v - percent / 100.).clamp(0., 1.))
+                    })),
+                    "lighten" => Ok(color.map_hsv(|hsv| {
+                        Hsv::new(hsv.h, hsv.s, (hsv.v + percent / 100.).clamp(0., 1.))
+                    })),
+                    "alpha" => Ok(color.with_alpha((percent.clamp(0., 100.) / 100. * 255.) as u8)),
+                    _ => Err(Error::new(format!(
+                        "Unknown color function '{function}' in '{expr}'"
+                    ))),
+                }
+            }
+            None => expr.parse(),
+        }
+    }
+
+    fn map_hsv(self, f: impl FnOnce(Hsv) -> Hsv) -> Self {
+        match self {
+            Self::Rgba(rgb, a) => Self::Hsva(f(rgb.into()), a),
+            Self::Hsva(hsv, a) => Self::Hsva(f(hsv), a),
+            other => other,
+        }
+    }
+
+    fn with_alpha(self, alpha: u8) -> Self {
+        match self {
+            Self::Rgba(rgb, _) => Self::Rgba(rgb, alpha),
+            Self::Hsva(hsv, _) => Self::Hsva(hsv, alpha),
+            other => other,
+        }
+    }
+}
+
+/// How the alternating tint is applied to a color
+#[derive(Debug, Clone, Copy, PartialEq, SmartDefault)]
+pub enum BlendMode {
+    /// Add the tint to the color, channel by channel
+    #[default]
+    Add,
+    /// Multiply the channels, which darkens the color
+    Multiply,
+    /// Multiply the inverted channels, which lightens the color
+    Screen,
+    /// Multiply dark channels and screen light ones, which increases the contrast
+    Overlay,
+    /// Increase the brightness by a percentage, ignoring the tint
+    Lighten(f64),
+    /// Decrease the brightness by a percentage, ignoring the tint
+    Darken(f64),
+    /// Move the color (including its alpha) towards the tint by a percentage
+    Mix(f64),
+}
+
+impl FromStr for BlendMode {
+    type Err = Error;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        let err_msg = || format!("'{mode}' is not a valid blend mode");
+        let percent = |args: &str| args.trim().parse::<f64>().or_error(err_msg);
+        Ok(match mode.trim() {
+            "add" => Self::Add,
+            "multiply" => Self::Multiply,
+            "screen" => Self::Screen,
+            "overlay" => Self::Overlay,
+            call => {
+                let (function, args) = call
+                    .strip_suffix(')')
+                    .and_then(|call| call.split_once('('))
+                    .or_error(err_msg)?;
+                match function.trim() {
+                    "lighten" => Self::Lighten(percent(args)?),
+                    "darken" => Self::Darken(percent(args)?),
+                    "mix" => Self::Mix(percent(args)?),
+                    _ => return Err(Error::new(err_msg())),
+                }
+            }
+        })
+    }
+}
+
+impl Color {
+    /// Apply `tint` to this color using `mode`. Colors which are not set are left unchanged,
+    /// except in the `add` mode, where the tint replaces them.
+    pub fn blend(self, tint: Color, mode: BlendMode) -> Color {
+        let per_channel = |f: fn(f64, f64) -> f64| match (self.rgba(), tint.rgba()) {
+            (Some((base, a)), Some((tint, _))) => Color::Rgba(
+                Rgb::new(f(base.r, tint.r), f(base.g, tint.g), f(base.b, tint.b)),
+                a,
+            ),
+            _ => self,
+        };
+        match mode {
+            BlendMode::Add => self + tint,
+            BlendMode::Multiply => per_channel(|a, b| a * b / 255.),
+            BlendMode::Screen => per_channel(|a, b| 255. - (255. - a) * (255. - b) / 255.),
+            BlendMode::Overlay => per_channel(|a, b| {
+                if a < 128. {
+                    2. * a * b / 255.
+                } else {
+                    255. - 2. * (255. - a) * (255. - b) / 255.
+                }
+            }),
+            BlendMode::Lighten(percent) => {
+                self.map_hsv(|hsv| Hsv::new(hsv.h, hsv.s, (hsv.v + percent / 100.).clamp(0., 1.)))
+            }
+            BlendMode::Darken(percent) => {
+                self.map_hsv(|hsv| Hsv::new(hsv.h, hsv.s, (hsv.v - percent / 100.).clamp(0., 1.)))
+            }
+            BlendMode::Mix(percent) => match (self.rgba(), tint.rgba()) {
+                (Some((base, a1)), Some((tint, a2))) => {
+                    let t = (percent / 100.).clamp(0., 1.);
+                    let mix = |a: f64, b: f64| a + (b - a) * t;
+                    Color::Rgba(
+                        Rgb::new(
+                            mix(base.r, tint.r),
+                            mix(base.g, tint.g),
+                            mix(base.b, tint.b),
+                        ),
+                        mix(a1.into(), a2.into()).round() as u8,
+                    )
+                }
+                _ => self,
+            },
+        }
+    }
+
+    fn rgba(self) -> Option<(Rgb, u8)> {
+        match self {
+            Self::Rgba(rgb, a) => Some((rgb, a)),
+            Self::Hsva(hsv, a) => Some((hsv.into(), a)),
+            Self::None | Self::Auto => None,
+        }
+    }
 }
 
 impl Add for Color {
@@ -132,3 +286,99 @@ impl<'de> Deserialize<'de> for Color {
         deserializer.deserialize_any(ColorVisitor)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expressions() {
+        let red: Color = "#ff0000".parse().unwrap();
+        let palette = |name: &str| match name {
+            "red" => Ok(red),
+            _ => Err(Error::new("unknown")),
+        };
+        let parse = |expr| Color::parse_expr(expr, &palette).unwrap();
+        assert_eq!(parse("$red"), red);
+        assert_eq!(parse("#00ff00"), "#00ff00".parse().unwrap());
+        assert_eq!(
+            parse("darken($red, 10)"),
+            Color::Hsva(Hsv::new(0., 1., 0.9), 255)
+        );
+        assert_eq!(
+            parse("alpha(lighten(darken($red, 30), 10), 50)"),
+            Color::Hsva(Hsv::new(0., 1., 0.8), 127)
+        );
+        assert!(Color::parse_expr("$blue", &palette).is_err());
+        assert!(Color::parse_expr("blend($red, 10)", &palette).is_err());
+    }
+
+    fn rgb(r: f64, g: f64, b: f64) -> Color {
+        Color::Rgba(Rgb::new(r, g, b), 255)
+    }
+
+    fn blend(base: Color, tint: Color, mode: &str) -> Color {
+        base.blend(tint, mode.parse().unwrap())
+    }
+
+    #[test]
+    fn blend_add() {
+        assert_eq!(
+            blend(rgb(200., 100., 0.), rgb(100., 10., 10.), "add"),
+            rgb(255., 110., 10.)
+        );
+        assert_eq!(blend(Color::None, rgb(1., 2., 3.), "add"), rgb(1., 2., 3.));
+    }
+
+    #[test]
+    fn blend_multiply() {
+        assert_eq!(
+            blend(rgb(255., 100., 0.), rgb(51., 255., 255.), "multiply"),
+            rgb(51., 100., 0.)
+        );
+        assert_eq!(blend(Color::None, rgb(1., 2., 3.), "multiply"), Color::None);
+    }
+
+    #[test]
+    fn blend_screen() {
+        assert_eq!(
+            blend(rgb(255., 0., 0.), rgb(0., 51., 255.), "screen"),
+            rgb(255., 51., 255.)
+        );
+    }
+
+    #[test]
+    fn blend_overlay() {
+        assert_eq!(
+            blend(rgb(0., 51., 255.), rgb(255., 255., 0.), "overlay"),
+            rgb(0., 102., 255.)
+        );
+    }
+
+    #[test]
+    fn blend_lighten_darken() {
+        let gray = Color::Hsva(Hsv::new(0., 0., 0.5), 255);
+        assert_eq!(
+            blend(gray, Color::None, "lighten(10)"),
+            Color::Hsva(Hsv::new(0., 0., 0.6), 255)
+        );
+        assert_eq!(
+            blend(gray, Color::None, "darken(60)"),
+            Color::Hsva(Hsv::new(0., 0., 0.), 255)
+        );
+    }
+
+    #[test]
+    fn blend_mix() {
+        assert_eq!(
+            blend(
+                rgb(0., 0., 0.),
+                Color::Rgba(Rgb::new(200., 100., 50.), 55),
+                "mix(50)"
+            ),
+            Color::Rgba(Rgb::new(100., 50., 25.), 155)
+        );
+        assert!("mix(a lot)".parse::<BlendMode>().is_err());
+        assert!("burn".parse::<BlendMode>().is_err());
+    }
+}
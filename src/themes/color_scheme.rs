@@ -0,0 +1,88 @@
+//! Following the desktop's light or dark color scheme, as announced by the
+//! `org.freedesktop.portal.Settings` interface of xdg-desktop-portal
+
+use futures::stream::{self, Stream, StreamExt};
+use zbus::dbus_proxy;
+use zvariant::{OwnedValue, Value};
+
+use crate::errors::*;
+use crate::util::new_dbus_connection;
+
+const NAMESPACE: &str = "org.freedesktop.appearance";
+const KEY: &str = "color-scheme";
+
+#[dbus_proxy(
+    interface = "org.freedesktop.portal.Settings",
+    default_service = "org.freedesktop.portal.Desktop",
+    default_path = "/org/freedesktop/portal/desktop"
+)]
+trait Settings {
+    fn read(&self, namespace: &str, key: &str) -> zbus::Result<OwnedValue>;
+
+    #[dbus_proxy(signal)]
+    fn setting_changed(&self, namespace: &str, key: &str, value: Value<'_>) -> zbus::Result<()>;
+}
+
+/// A stream which yields `true` whenever the desktop switches to the dark color scheme and `false`
+/// when it switches to the light one, starting with the current scheme. If the portal is not
+/// available, a warning is printed and the stream never yields.
+pub fn dark_mode_stream() -> impl Stream<Item = bool> {
+    stream::once(async {
+        match watch_dark_mode().await {
+            Ok(updates) => updates.boxed(),
+            Err(error) => {
+                eprintln!("Warning: cannot follow the desktop's color scheme: {error}");
+                stream::pending().boxed()
+            }
+        }
+    })
+    .flatten()
+}
+
+async fn watch_dark_mode() -> Result<impl Stream<Item = bool>> {
+    let conn = new_dbus_connection().await?;
+    let proxy = SettingsProxy::new(&conn)
+        .await
+        .error("Failed to create portal settings proxy")?;
+    let updates = proxy
+        .receive_setting_changed()
+        .await
+        .error("Failed to monitor portal settings")?;
+    let current = proxy
+        .read(NAMESPACE, KEY)
+        .await
+        .error("Failed to read the color scheme")?;
+    Ok(
+        stream::iter(is_dark(&current)).chain(updates.filter_map(|signal| async move {
+            let args = signal.args().ok()?;
+            if *args.namespace() == NAMESPACE && *args.key() == KEY {
+                is_dark(args.value())
+            } else {
+                None
+            }
+        })),
+    )
+}
+
+/// `1` means "prefer dark", `2` "prefer light" and `0` "no preference"
+fn is_dark(value: &Value) -> Option<bool> {
+    match value {
+        // `Read` wraps the value in another variant
+        Value::Value(inner) => is_dark(inner),
+        Value::U32(scheme) => Some(*scheme == 1),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_schemes() {
+        assert_eq!(is_dark(&Value::U32(1)), Some(true));
+        assert_eq!(is_dark(&Value::U32(0)), Some(false));
+        assert_eq!(is_dark(&Value::Value(Box::new(Value::U32(1)))), Some(true));
+        assert_eq!(is_dark(&Value::from("dark")), None);
+    }
+}
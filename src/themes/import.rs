@@ -0,0 +1,208 @@
+//! Importing colors from palettes generated by other tools, such as pywal or base16 builders
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use futures::stream::{Stream, StreamExt};
+use inotify::{Inotify, WatchMask};
+use serde::Deserialize;
+
+use crate::errors::*;
+use crate::subprocess::run_command_stdout_blocking;
+
+/// How long `xrdb -query` may take
+const XRDB_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PaletteSource {
+    /// `colors.json` as written by pywal
+    Pywal,
+    /// `*.colorN`, `*.background` and `*.foreground` resources
+    Xresources,
+    /// A base16 scheme in YAML
+    Base16,
+}
+
+impl PaletteSource {
+    /// The file read if `source_file` is not set. Xresources are queried with `xrdb -query`.
+    fn default_file(self) -> Option<&'static str> {
+        match self {
+            Self::Pywal => Some("~/.cache/wal/colors.json"),
+            Self::Xresources | Self::Base16 => None,
+        }
+    }
+
+    /// Which palette slot is used for each theme key, unless set in `mapping`
+    fn default_mapping(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Pywal | Self::Xresources => &[
+                ("idle_bg", "background"),
+                ("idle_fg", "foreground"),
+                ("info_bg", "color4"),
+                ("info_fg", "background"),
+                ("good_bg", "color2"),
+                ("good_fg", "background"),
+                ("warning_bg", "color3"),
+                ("warning_fg", "background"),
+                ("critical_bg", "color1"),
+                ("critical_fg", "background"),
+            ],
+            Self::Base16 => &[
+                ("idle_bg", "base00"),
+                ("idle_fg", "base05"),
+                ("info_bg", "base0D"),
+                ("info_fg", "base00"),
+                ("good_bg", "base0B"),
+                ("good_fg", "base00"),
+                ("warning_bg", "base0A"),
+                ("warning_fg", "base00"),
+                ("critical_bg", "base08"),
+                ("critical_fg", "base00"),
+            ],
+        }
+    }
+}
+
+/// The file the palette is read from, if any
+pub fn palette_file(source: PaletteSource, file: Option<&str>) -> Option<PathBuf> {
+    file.or_else(|| source.default_file())
+        .map(|file| PathBuf::from(shellexpand::tilde(file).as_ref()))
+}
+
+/// Read the palette and turn it into theme overrides. `mapping` maps theme keys (e.g. `idle_bg`)
+/// to palette slots (e.g. `color0`), on top of the default mapping of the source.
+pub fn palette_overrides(
+    source: PaletteSource,
+    file: Option<&Path>,
+    mapping: Option<&HashMap<String, String>>,
+) -> Result<HashMap<String, String>> {
+    let palette = read_palette(source, file)?;
+    let mut keys: HashMap<&str, &str> = source.default_mapping().iter().copied().collect();
+    for (key, slot) in mapping.into_iter().flatten() {
+        keys.insert(key, slot);
+    }
+    keys.into_iter()
+        .map(|(key, slot)| {
+            let color = palette
+                .get(slot)
+                .or_error(|| format!("Palette slot '{slot}' (used for '{key}') not found"))?;
+            Ok((key.to_string(), color.clone()))
+        })
+        .collect()
+}
+
+fn read_palette(source: PaletteSource, file: Option<&Path>) -> Result<HashMap<String, String>> {
+    let contents = match file {
+        Some(file) => std::fs::read_to_string(file)
+            .or_error(|| format!("Failed to read palette '{}'", file.display()))?,
+        None if source == PaletteSource::Xresources => {
+            let mut xrdb = tokio::process::Command::new("xrdb");
+            xrdb.arg("-query");
+            run_command_stdout_blocking(xrdb, XRDB_TIMEOUT)?
+        }
+        None => {
+            return Err(Error::new(
+                "'source_file' is required for this palette source",
+            ))
+        }
+    };
+    match source {
+        PaletteSource::Pywal => parse_pywal(&contents),
+        PaletteSource::Xresources => Ok(parse_xresources(&contents)),
+        PaletteSource::Base16 => Ok(parse_base16(&contents)),
+    }
+}
+
+/// Slots are `background`, `foreground`, `cursor` and `color0` to `color15`
+fn parse_pywal(contents: &str) -> Result<HashMap<String, String>> {
+    #[derive(Deserialize)]
+    struct Pywal {
+        special: HashMap<String, String>,
+        colors: HashMap<String, String>,
+    }
+    let pywal: Pywal = serde_json::from_str(contents).error("Invalid pywal colors")?;
+    Ok(pywal.special.into_iter().chain(pywal.colors).collect())
+}
+
+/// Slots are the resource names without the class, e.g. `color1` for `*.color1` or `URxvt*color1`
+fn parse_xresources(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .filter(|line| !line.starts_with('!') && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let slot = name.trim().rsplit(['*', '.']).next()?;
+            Some((slot.to_string(), normalize_color(value.trim())?))
+        })
+        .collect()
+}
+
+/// Slots are `base00` to `base0F`
+fn parse_base16(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            let value = value.split(" #").next()?.trim().trim_matches('"');
+            Some((name.trim().to_string(), normalize_color(value)?))
+        })
+        .collect()
+}
+
+/// Convert `#rrggbb`, `rrggbb` and `rgb:rr/gg/bb` colors to `#rrggbb`
+fn normalize_color(value: &str) -> Option<String> {
+    let hex = match value.strip_prefix("rgb:") {
+        Some(rgb) => rgb.split('/').collect(),
+        None => value.trim_start_matches('#').to_string(),
+    };
+    (hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit())).then(|| format!("#{hex}"))
+}
+
+/// A stream which yields each time `file` is written or replaced
+pub fn file_updates(file: &Path) -> Result<impl Stream<Item = ()>> {
+    let dir = file
+        .parent()
+        .or_error(|| format!("Cannot watch '{}'", file.display()))?;
+    let name: Option<OsString> = file.file_name().map(Into::into);
+    let mut notify = Inotify::init().error("Failed to start inotify")?;
+    // Watch the directory, because tools usually replace the file rather than writing to it
+    notify
+        .add_watch(dir, WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO)
+        .or_error(|| format!("Failed to watch '{}'", dir.display()))?;
+    Ok(notify
+        .event_stream([0; 1024])
+        .error("Failed to create event stream")?
+        .filter_map(move |event| {
+            let updated = matches!(event, Ok(event) if event.name == name);
+            async move { updated.then_some(()) }
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palettes() {
+        let xresources =
+            "! comment\n*.background: #1d1f21\nURxvt*color1:\trgb:cc/66/66\n*.font: mono\n";
+        let palette = parse_xresources(xresources);
+        assert_eq!(palette["background"], "#1d1f21");
+        assert_eq!(palette["color1"], "#cc6666");
+        assert!(!palette.contains_key("font"));
+
+        let base16 = "scheme: \"Gruvbox\"\nbase00: \"282828\" # bg\nbase08: \"#fb4934\"\n";
+        let palette = parse_base16(base16);
+        assert_eq!(palette["base00"], "#282828");
+        assert_eq!(palette["base08"], "#fb4934");
+        assert!(!palette.contains_key("scheme"));
+
+        let pywal = r##"{"special": {"background": "#000000"}, "colors": {"color1": "#ff0000"}}"##;
+        let palette = parse_pywal(pywal).unwrap();
+        assert_eq!(palette["background"], "#000000");
+        assert_eq!(palette["color1"], "#ff0000");
+    }
+}
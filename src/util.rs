@@ -77,21 +77,11 @@ pub async fn new_system_dbus_connection() -> Result<zbus::Connection> {
         .error("Failed to open DBus system connection")
 }
 
-pub fn battery_level_icon(level: u8, charging: bool) -> &'static str {
-    match (level, charging) {
-        // TODO: use different charging icons
-        (_, true) => "bat_charging",
-        (0..=10, _) => "bat_10",
-        (11..=20, _) => "bat_20",
-        (21..=30, _) => "bat_30",
-        (31..=40, _) => "bat_40",
-        (41..=50, _) => "bat_50",
-        (51..=60, _) => "bat_60",
-        (61..=70, _) => "bat_70",
-        (71..=80, _) => "bat_80",
-        (81..=90, _) => "bat_90",
-        _ => "bat_full",
-    }
+/// The position in the `bat` icon family for a battery level in percent. With ten icons, the
+/// first one is used up to 10%, the second one from 11% to 20% and so on, and the last one above
+/// 90%.
+pub fn battery_level_fraction(level: f64) -> f64 {
+    ((level - 1.) / 100.).clamp(0., 1.)
 }
 
 pub fn deserialize_toml_file<T>(path: &Path) -> Result<T>
@@ -1,8 +1,11 @@
+use crate::click::SLIDER_INSTANCE;
 use crate::config::SharedConfig;
 use crate::errors::*;
-use crate::escape::CollectEscaped;
+use crate::escape::{unescape_pango, CollectEscaped};
+use crate::formatting::value::ValueInner;
 use crate::formatting::{Format, Fragment, Values};
 use crate::protocol::i3bar_block::I3BarBlock;
+use crate::themes::Theme;
 use serde::Deserialize;
 
 #[derive(Debug, Clone)]
@@ -11,6 +14,9 @@ pub struct Widget {
     pub state: State,
     id: usize,
     source: Source,
+    /// Whether the format is the block's main format, which click actions like
+    /// `toggle_format_alt` replace, rather than a format for a particular state
+    main_format: bool,
 }
 
 impl Widget {
@@ -20,6 +26,7 @@ impl Widget {
             state: State::Idle,
             id,
             source: Source::Text(String::new()),
+            main_format: true,
         }
     }
 
@@ -65,14 +72,83 @@ impl Widget {
             Source::Format(old, _) => *old = format,
             _ => self.source = Source::Format(format, None),
         }
+        self.main_format = true;
+    }
+
+    /// Set a format for a particular state of the block (e.g. `missing_format`), which is shown
+    /// as is even when the main format is replaced by `format_alt` and the like
+    pub fn set_state_format(&mut self, format: Format) {
+        self.set_format(format);
+        self.main_format = false;
     }
 
     pub fn set_values(&mut self, new_values: Values) {
-        if let Source::Format(_, values) = &mut self.source {
+        if let Source::Format(format, values) = &mut self.source {
+            format.update(&new_values);
             *values = Some(new_values);
         }
     }
 
+    pub fn has_main_format(&self) -> bool {
+        self.main_format
+    }
+
+    pub fn format(&self) -> Option<&Format> {
+        match &self.source {
+            Source::Format(f, _) => Some(f),
+            _ => None,
+        }
+    }
+
+    /// The text of the placeholder `name` as plain text (not escaped for pango). Numbers are
+    /// formatted with the default formatter.
+    pub fn value_text(&self, name: &str) -> Option<String> {
+        match &self.source {
+            Source::Format(_, Some(values)) => {
+                let value = values.get(name)?;
+                match &value.inner {
+                    ValueInner::Text(text) | ValueInner::Icon(text) => Some(text.clone()),
+                    ValueInner::List(_) => None,
+                    inner => value
+                        .default_formatter()
+                        .format(inner, &self.shared_config)
+                        .ok(),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// The values of an empty and a full slider, if the widget displays one
+    pub fn slider_range(&self) -> Option<(f64, f64)> {
+        match &self.source {
+            Source::Format(format, Some(values)) => {
+                format
+                    .render(values, &self.shared_config)
+                    .ok()?
+                    .0
+                    .iter()
+                    .find(|fragment| fragment.metadata.instance == Some(SLIDER_INSTANCE))?
+                    .metadata
+                    .slider
+            }
+            _ => None,
+        }
+    }
+
+    /// Render `format` with the values of this widget, as plain text
+    pub fn render_with(&self, format: &Format) -> Result<String> {
+        match &self.source {
+            Source::Format(_, Some(values)) => Ok(format
+                .render(values, &self.shared_config)?
+                .0
+                .iter()
+                .map(|fragment| unescape_pango(&fragment.text))
+                .collect()),
+            _ => Ok(String::new()),
+        }
+    }
+
     pub fn intervals(&self) -> Vec<u64> {
         match &self.source {
             Source::Format(f, _) => f.intervals(),
@@ -80,10 +156,10 @@ impl Widget {
         }
     }
 
-    /// Constuct `I3BarBlock` from this widget
-    pub fn get_data(&self) -> Result<Vec<I3BarBlock>> {
+    /// Constuct `I3BarBlock` from this widget, colored with `theme`
+    pub fn get_data(&self, theme: &Theme) -> Result<Vec<I3BarBlock>> {
         // Create a "template" block
-        let (key_bg, key_fg) = self.shared_config.theme.get_colors(self.state);
+        let (key_bg, key_fg) = theme.get_colors(self.state);
         let (full, short) = self.source.render(&self.shared_config)?;
         let mut template = I3BarBlock {
             name: Some(self.id.to_string()),
@@ -134,6 +210,10 @@ pub enum State {
     Good,
     Warning,
     Critical,
+    /// E.g. a muted sound device. Shown like `Warning` unless the theme sets `muted_bg/fg`.
+    Muted,
+    /// E.g. a disconnected device. Shown like `Idle` unless the theme sets `disconnected_bg/fg`.
+    Disconnected,
 }
 
 impl Default for State {
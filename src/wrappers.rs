@@ -1,7 +1,11 @@
-use crate::errors::{Result, ResultExt};
-use serde::de::{self, Deserialize, Deserializer};
+use crate::errors::{Error, Result, ResultExt};
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
 use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::OnceCell;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Seconds(pub Duration);
@@ -119,3 +123,116 @@ impl ShellString {
         shellexpand::full(&self.0).error("Failed to expand string")
     }
 }
+
+/// How long a command which prints a secret may take
+const SECRET_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A credential, such as a token or an API key. It can be given literally, or read from a file,
+/// the output of a command or an environment variable:
+///
+/// ```toml
+/// token = { file = "~/.secrets/github" }
+/// token = { command = "pass show github" }
+/// token = { env = "GITHUB_TOKEN" }
+/// ```
+///
+/// The secret is read when it is first needed and then cached. Its value is never shown in
+/// `Debug` output or error messages.
+#[derive(Deserialize, Clone)]
+#[serde(from = "SecretSource")]
+pub struct Secret {
+    source: SecretSource,
+    value: Arc<OnceCell<String>>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(untagged)]
+enum SecretSource {
+    Literal(String),
+    File { file: String },
+    Command { command: String },
+    Env { env: String },
+}
+
+impl From<SecretSource> for Secret {
+    fn from(source: SecretSource) -> Self {
+        Self {
+            source,
+            value: Default::default(),
+        }
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            SecretSource::Literal(_) => f.write_str("Secret(***)"),
+            SecretSource::File { file } => f.debug_struct("Secret").field("file", file).finish(),
+            SecretSource::Command { command } => {
+                f.debug_struct("Secret").field("command", command).finish()
+            }
+            SecretSource::Env { env } => f.debug_struct("Secret").field("env", env).finish(),
+        }
+    }
+}
+
+impl Secret {
+    /// A secret read from the environment variable `name`
+    pub fn env(name: &str) -> Self {
+        SecretSource::Env { env: name.into() }.into()
+    }
+
+    pub async fn get(&self) -> Result<&str> {
+        self.value
+            .get_or_try_init(|| async {
+                let value = match &self.source {
+                    SecretSource::Literal(value) => value.clone(),
+                    SecretSource::File { file } => {
+                        let path = shellexpand::tilde(file);
+                        tokio::fs::read_to_string(path.as_ref())
+                            .await
+                            .or_error(|| format!("Failed to read secret from '{path}'"))?
+                    }
+                    SecretSource::Command { command } => {
+                        crate::subprocess::run_command_stdout(
+                            tokio::process::Command::new("sh").args(["-c", command]),
+                            SECRET_COMMAND_TIMEOUT,
+                        )
+                        .await?
+                    }
+                    SecretSource::Env { env } => std::env::var(env)
+                        .or_error(|| format!("Environment variable '{env}' is not set"))?,
+                };
+                let value = value.trim_end_matches(['\r', '\n']);
+                if value.is_empty() {
+                    return Err(Error::new(format!("{self:?} is empty")));
+                }
+                Ok(value.to_string())
+            })
+            .await
+            .map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn secrets() {
+        #[derive(Deserialize)]
+        struct Config {
+            literal: Secret,
+            command: Secret,
+            env: Secret,
+        }
+        let config: Config = toml::from_str(
+            "literal = \"hunter2\"\ncommand = { command = \"echo s3cret\" }\nenv = { env = \"I3RS_MISSING_SECRET\" }",
+        )
+        .unwrap();
+        assert_eq!(config.literal.get().await.unwrap(), "hunter2");
+        assert_eq!(config.command.get().await.unwrap(), "s3cret");
+        assert!(config.env.get().await.is_err());
+        assert!(!format!("{:?}", config.literal).contains("hunter2"));
+    }
+}